@@ -1,4 +1,10 @@
-use crate::chain::{ChainExecutionMode, CommandChain};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::builder::BuildError;
+use crate::chain::graph::DependencyGraph;
+use crate::chain::{AfterHook, BeforeHook, ChainExecutionMode, CommandChain, ProgressHook};
 use crate::command::Command;
 use crate::logging::Logger;
 
@@ -15,6 +21,42 @@ pub struct ChainBuilder {
 
     /// Откатывать ли выполненные команды в случае ошибки
     rollback_on_error: bool,
+
+    /// Выводить ли команды перед выполнением
+    echo_commands: bool,
+
+    /// Режим пробного запуска (команды не выполняются)
+    dry_run: bool,
+
+    /// Записывать ли итоговую строку в лог по завершении запуска
+    log_summary: bool,
+
+    /// Общий таймаут выполнения цепочки
+    chain_timeout: Option<Duration>,
+
+    /// Обработчик, вызываемый перед выполнением каждой команды
+    before_each: Option<BeforeHook>,
+
+    /// Обработчик, вызываемый после выполнения каждой команды
+    after_each: Option<AfterHook>,
+
+    /// Обработчик хода выполнения цепочки
+    on_progress: Option<ProgressHook>,
+
+    /// Переменные окружения для всех команд цепочки
+    env_vars: HashMap<String, String>,
+
+    /// Разделять ли значения переменных между командами цепочки
+    share_variables: bool,
+
+    /// Таймаут для команд, для которых он не задан явно
+    default_timeout: Option<Duration>,
+
+    /// Значения переменных, имеющие наивысший приоритет
+    overrides: HashMap<String, String>,
+
+    /// Команды цепочки
+    commands: Vec<Arc<dyn Command>>,
 }
 
 impl ChainBuilder {
@@ -25,6 +67,18 @@ impl ChainBuilder {
             mode: ChainExecutionMode::Sequential,
             logger: None,
             rollback_on_error: true,
+            echo_commands: false,
+            dry_run: false,
+            log_summary: true,
+            chain_timeout: None,
+            before_each: None,
+            after_each: None,
+            on_progress: None,
+            env_vars: HashMap::new(),
+            share_variables: false,
+            default_timeout: None,
+            overrides: HashMap::new(),
+            commands: Vec::new(),
         }
     }
 
@@ -46,19 +100,116 @@ impl ChainBuilder {
         self
     }
 
-    /// Строит цепочку команд
-    pub fn build(self) -> CommandChain {
-        let mut chain = CommandChain::new(&self.name);
+    /// Устанавливает флаг вывода команд перед выполнением (аналог `set -x`)
+    ///
+    /// Строки вывода команд при этом передаются логгеру цепочки.
+    pub fn echo_commands(mut self, echo: bool) -> Self {
+        self.echo_commands = echo;
+        self
+    }
 
-        chain
-            .with_execution_mode(self.mode)
-            .with_rollback_on_error(self.rollback_on_error);
+    /// Устанавливает режим пробного запуска: команды только логируются, а не выполняются
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
 
-        if let Some(logger) = self.logger {
-            chain.with_logger(logger);
+    /// Устанавливает запись итоговой строки в лог по завершении запуска (по умолчанию включена)
+    pub fn log_summary(mut self, enabled: bool) -> Self {
+        self.log_summary = enabled;
+        self
+    }
+
+    /// Устанавливает общий таймаут выполнения цепочки
+    pub fn chain_timeout(mut self, timeout: Duration) -> Self {
+        self.chain_timeout = Some(timeout);
+        self
+    }
+
+    /// Устанавливает обработчик, вызываемый перед выполнением каждой команды
+    pub fn before_each(mut self, hook: BeforeHook) -> Self {
+        self.before_each = Some(hook);
+        self
+    }
+
+    /// Устанавливает обработчик, вызываемый после выполнения каждой команды
+    pub fn after_each(mut self, hook: AfterHook) -> Self {
+        self.after_each = Some(hook);
+        self
+    }
+
+    /// Устанавливает обработчик хода выполнения `(завершено, всего)`,
+    /// вызываемый после завершения каждой команды
+    pub fn on_progress(mut self, hook: ProgressHook) -> Self {
+        self.on_progress = Some(hook);
+        self
+    }
+
+    /// Добавляет переменную окружения для всех команд цепочки
+    ///
+    /// Переменные, заданные в самой команде, имеют приоритет.
+    pub fn env_var(mut self, key: &str, value: &str) -> Self {
+        self.env_vars.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    /// Устанавливает таймаут для команд цепочки, для которых он не задан явно
+    pub fn default_timeout(mut self, timeout: Duration) -> Self {
+        self.default_timeout = Some(timeout);
+        self
+    }
+
+    /// Устанавливает разделение значений переменных между командами цепочки
+    ///
+    /// Значение каждой переменной запрашивается один раз за запуск цепочки.
+    pub fn share_variables(mut self, share: bool) -> Self {
+        self.share_variables = share;
+        self
+    }
+
+    /// Задает значения переменных, имеющие приоритет над файлом переменных и окружением
+    pub fn overrides(mut self, overrides: HashMap<String, String>) -> Self {
+        self.overrides.extend(overrides);
+        self
+    }
+
+    /// Добавляет команду в цепочку
+    pub fn command<C: Command + 'static>(mut self, command: C) -> Self {
+        self.commands.push(Arc::new(command));
+        self
+    }
+
+    /// Проверяет конфигурацию цепочки
+    fn validate(&self) -> Result<(), BuildError> {
+        if self.name.trim().is_empty() {
+            return Err(BuildError::EmptyName);
         }
 
-        chain
+        let has_dependencies = self
+            .commands
+            .iter()
+            .any(|command| !command.dependencies().is_empty());
+
+        if self.mode == ChainExecutionMode::Dag || has_dependencies {
+            DependencyGraph::build(&self.commands)?;
+        }
+
+        Ok(())
+    }
+
+    /// Строит цепочку команд, предварительно проверив конфигурацию
+    pub fn try_build(self) -> Result<CommandChain, BuildError> {
+        self.validate()?;
+
+        Ok(self.assemble())
+    }
+
+    /// Строит цепочку команд
+    ///
+    /// Конфигурация не проверяется: ошибки зависимостей обнаруживаются при
+    /// выполнении цепочки. Для проверки при построении используйте `try_build`.
+    pub fn build(self) -> CommandChain {
+        self.assemble()
     }
 
     /// Строит цепочку команд с набором начальных команд
@@ -66,10 +217,55 @@ impl ChainBuilder {
     where
         C: Command + 'static,
     {
-        let mut chain = self.build();
+        commands
+            .into_iter()
+            .fold(self, |builder, command| builder.command(command))
+            .build()
+    }
+
+    /// Создает цепочку из настроек строителя
+    fn assemble(self) -> CommandChain {
+        let mut chain = CommandChain::new(&self.name);
+
+        chain
+            .with_execution_mode(self.mode)
+            .with_rollback_on_error(self.rollback_on_error)
+            .with_echo_commands(self.echo_commands)
+            .with_dry_run(self.dry_run)
+            .with_log_summary(self.log_summary)
+            .with_share_variables(self.share_variables)
+            .with_overrides(self.overrides);
+
+        if let Some(timeout) = self.chain_timeout {
+            chain.with_chain_timeout(timeout);
+        }
+
+        if let Some(timeout) = self.default_timeout {
+            chain.with_default_timeout(timeout);
+        }
+
+        if let Some(hook) = self.before_each {
+            chain.with_before_each(hook);
+        }
+
+        if let Some(hook) = self.after_each {
+            chain.with_after_each(hook);
+        }
+
+        if let Some(hook) = self.on_progress {
+            chain.with_on_progress(hook);
+        }
+
+        if let Some(logger) = self.logger {
+            chain.with_logger(logger);
+        }
+
+        for (key, value) in &self.env_vars {
+            chain.with_env_var(key, value);
+        }
 
-        for command in commands {
-            chain.add_command(command);
+        for command in self.commands {
+            chain.add_shared_command(command);
         }
 
         chain
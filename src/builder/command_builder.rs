@@ -1,8 +1,16 @@
 use std::collections::HashMap;
+use std::time::Duration;
 
-use crate::command::{ExecutionMode, ShellCommand};
+use regex::Regex;
+
+use crate::builder::BuildError;
+
+use crate::command::{
+    ExecutionMode, OutputParser, ParsedResult, RetryPolicy, SandboxConfig, ShellCommand,
+};
 
 /// Строитель для команд (паттерн Строитель)
+#[derive(Clone)]
 pub struct CommandBuilder {
     /// Название команды
     name: String,
@@ -13,20 +21,110 @@ pub struct CommandBuilder {
     /// Рабочая директория для выполнения команды
     working_dir: Option<String>,
 
+    /// Создавать ли рабочую директорию, если она не существует
+    create_working_dir: bool,
+
     /// Переменные окружения
     env_vars: HashMap<String, String>,
 
+    /// Не наследовать окружение родительского процесса
+    clean_env: bool,
+
+    /// Наследуемые переменные окружения, удаляемые перед запуском
+    env_remove: Vec<String>,
+
+    /// Путь к файлу с переменными окружения в формате `.env`
+    env_file: Option<String>,
+
     /// Режим выполнения
     mode: ExecutionMode,
 
     /// Команда для отката
     rollback_command: Option<String>,
 
-    /// Таймаут выполнения команды в секундах
-    timeout_seconds: Option<u64>,
+    /// Таймаут выполнения команды
+    timeout: Option<Duration>,
+
+    /// Время между SIGTERM и SIGKILL при завершении по таймауту
+    kill_grace: Option<Duration>,
+
+    /// Приоритет запуска при параллельном выполнении
+    priority: i32,
+
+    /// Подставлять ли переменные окружения `%VAR%` самой библиотекой
+    windows_env_expansion: bool,
 
     /// Путь к файлу с переменными
     variables_file: Option<String>,
+
+    /// Выводить ли команду перед выполнением
+    echo: bool,
+
+    /// Скрывать ли интерактивно введенные значения в отображаемой командной строке
+    redact_variables: bool,
+
+    /// Пользовательский обработчик вывода команды
+    output_parser: Option<OutputParser>,
+
+    /// Маска прав создаваемых файлов (только Unix)
+    umask: Option<u32>,
+
+    /// Коды возврата, считающиеся успешными
+    success_codes: Option<Vec<i32>>,
+
+    /// Настройки изоляции команды
+    sandbox: Option<SandboxConfig>,
+
+    /// Выполнять ли команду без изоляции, если она не поддерживается
+    sandbox_optional: bool,
+
+    /// Данные, передаваемые команде на стандартный ввод
+    stdin: Option<String>,
+
+    /// Интервал периодического логирования выполнения команды
+    heartbeat: Option<Duration>,
+
+    /// Исключена ли команда из отката цепочки
+    skip_rollback: bool,
+
+    /// Имя переменной, в которую сохраняется вывод команды
+    capture_as: Option<String>,
+
+    /// Имена команд, от которых зависит команда (режим DAG)
+    depends_on: Vec<String>,
+
+    /// Максимальный размер сохраняемого вывода каждого потока в байтах
+    max_output_bytes: Option<usize>,
+
+    /// Интерпретатор и флаг для передачи ему командной строки
+    shell: Option<(String, String)>,
+
+    /// Произвольные метки команды
+    tags: HashMap<String, String>,
+
+    /// Постоянный идентификатор результатов команды
+    id: Option<String>,
+
+    /// Сохранять стандартный вывод без декодирования
+    binary_output: bool,
+
+    /// Шаблон вывода, интервал и количество попыток ожидания
+    wait_until: Option<(Regex, Duration, u32)>,
+
+    /// Подстрока, которую должен содержать вывод команды
+    expect_output_contains: Option<String>,
+
+    /// Шаблон, с которым должен совпадать вывод команды
+    expect_output_matches: Option<Regex>,
+
+    /// Количество повторных попыток неудачного отката
+    rollback_retries: u32,
+
+    /// Задержки между повторными попытками отката
+    rollback_retry_policy: Option<RetryPolicy>,
+
+    /// Команда, выполняемая только при ошибке этой команды
+    on_error: Option<String>,
 }
 
 impl CommandBuilder {
@@ -36,11 +134,41 @@ impl CommandBuilder {
             name: name.to_string(),
             command: command.to_string(),
             working_dir: None,
+            create_working_dir: false,
             env_vars: HashMap::new(),
+            clean_env: false,
+            env_remove: Vec::new(),
+            env_file: None,
             mode: ExecutionMode::Sequential,
             rollback_command: None,
-            timeout_seconds: None,
+            timeout: None,
+            kill_grace: None,
+            priority: 0,
+            windows_env_expansion: false,
             variables_file: None,
+            echo: false,
+            redact_variables: false,
+            output_parser: None,
+            umask: None,
+            success_codes: None,
+            sandbox: None,
+            sandbox_optional: false,
+            stdin: None,
+            heartbeat: None,
+            skip_rollback: false,
+            capture_as: None,
+            depends_on: Vec::new(),
+            max_output_bytes: None,
+            shell: None,
+            tags: HashMap::new(),
+            id: None,
+            binary_output: false,
+            wait_until: None,
+            expect_output_contains: None,
+            expect_output_matches: None,
+            rollback_retries: 0,
+            rollback_retry_policy: None,
+            on_error: None,
         }
     }
 
@@ -50,12 +178,50 @@ impl CommandBuilder {
         self
     }
 
+    /// Создает рабочую директорию перед запуском, если она не существует (аналог `mkdir -p`)
+    pub fn create_working_dir(mut self, create: bool) -> Self {
+        self.create_working_dir = create;
+        self
+    }
+
     /// Добавляет переменную окружения
     pub fn env_var(mut self, key: &str, value: &str) -> Self {
         self.env_vars.insert(key.to_string(), value.to_string());
         self
     }
 
+    /// Запускает команду без наследования окружения родительского процесса
+    pub fn clean_env(mut self) -> Self {
+        self.clean_env = true;
+        self
+    }
+
+    /// Удаляет наследуемую переменную окружения перед запуском команды
+    pub fn env_remove(mut self, key: &str) -> Self {
+        self.env_remove.push(key.to_string());
+        self
+    }
+
+    /// Загружает переменные окружения из файла `.env` при запуске команды
+    ///
+    /// Переменные, заданные через `env_var`, имеют приоритет над значениями из файла.
+    pub fn env_file(mut self, path: &str) -> Self {
+        self.env_file = Some(path.to_string());
+        self
+    }
+
+    /// Устанавливает постоянный идентификатор результатов команды вместо случайного UUID
+    pub fn id(mut self, id: &str) -> Self {
+        self.id = Some(id.to_string());
+        self
+    }
+
+    /// Добавляет метку команды (команда, критичность, номер задачи и т.п.)
+    pub fn tag(mut self, key: &str, value: &str) -> Self {
+        self.tags.insert(key.to_string(), value.to_string());
+        self
+    }
+
     /// Устанавливает режим выполнения
     pub fn execution_mode(mut self, mode: ExecutionMode) -> Self {
         self.mode = mode;
@@ -68,9 +234,59 @@ impl CommandBuilder {
         self
     }
 
+    /// Устанавливает количество повторных попыток неудачного отката
+    pub fn rollback_retries(mut self, retries: u32) -> Self {
+        self.rollback_retries = retries;
+        self
+    }
+
+    /// Устанавливает задержки между повторными попытками отката
+    pub fn rollback_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.rollback_retry_policy = Some(policy);
+        self
+    }
+
+    /// Устанавливает команду, выполняемую только при ошибке этой команды
+    ///
+    /// В отличие от `rollback`, не выполняется при откате цепочки из-за ошибки другой команды.
+    pub fn on_error(mut self, command: &str) -> Self {
+        self.on_error = Some(command.to_string());
+        self
+    }
+
+    /// Исключает команду из отката цепочки, даже если команда отката задана
+    pub fn no_rollback(mut self) -> Self {
+        self.skip_rollback = true;
+        self
+    }
+
+    /// Устанавливает таймаут выполнения в секундах
+    pub fn timeout(self, seconds: u64) -> Self {
+        self.timeout_duration(Duration::from_secs(seconds))
+    }
+
     /// Устанавливает таймаут выполнения
-    pub fn timeout(mut self, seconds: u64) -> Self {
-        self.timeout_seconds = Some(seconds);
+    pub fn timeout_duration(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Устанавливает время ожидания завершения команды после SIGTERM при таймауте
+    pub fn kill_grace(mut self, grace: Duration) -> Self {
+        self.kill_grace = Some(grace);
+        self
+    }
+
+    /// Устанавливает приоритет запуска при параллельном выполнении с ограничением
+    /// числа одновременных команд (большее значение запускается раньше)
+    pub fn priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Включает подстановку переменных окружения вида `%VAR%` самой библиотекой
+    pub fn windows_env_expansion(mut self, enabled: bool) -> Self {
+        self.windows_env_expansion = enabled;
         self
     }
 
@@ -80,10 +296,174 @@ impl CommandBuilder {
         self
     }
 
+    /// Устанавливает флаг вывода команды перед выполнением (аналог `set -x`)
+    pub fn echo(mut self, echo: bool) -> Self {
+        self.echo = echo;
+        self
+    }
+
+    /// Скрывает интерактивно введенные значения в выводе команды и результате
+    pub fn redact_variables(mut self) -> Self {
+        self.redact_variables = true;
+        self
+    }
+
+    /// Устанавливает обработчик вывода, определяющий успешность и данные результата
+    pub fn output_parser<F>(mut self, parser: F) -> Self
+    where
+        F: Fn(&str, Option<i32>) -> ParsedResult + Send + Sync + 'static,
+    {
+        self.output_parser = Some(OutputParser::new(parser));
+        self
+    }
+
+    /// Устанавливает маску прав создаваемых файлов (на Windows игнорируется)
+    pub fn umask(mut self, mask: u32) -> Self {
+        self.umask = Some(mask);
+        self
+    }
+
+    /// Устанавливает коды возврата, считающиеся успешными (по умолчанию только 0)
+    pub fn success_codes(mut self, codes: Vec<i32>) -> Self {
+        self.success_codes = Some(codes);
+        self
+    }
+
+    /// Устанавливает изоляцию команды (только Linux, требуется bubblewrap)
+    pub fn sandbox(mut self, config: SandboxConfig) -> Self {
+        self.sandbox = Some(config);
+        self
+    }
+
+    /// Разрешает выполнение без изоляции на платформах, где она не поддерживается
+    pub fn sandbox_optional(mut self, optional: bool) -> Self {
+        self.sandbox_optional = optional;
+        self
+    }
+
+    /// Устанавливает данные, передаваемые команде на стандартный ввод
+    pub fn stdin(mut self, data: impl Into<String>) -> Self {
+        self.stdin = Some(data.into());
+        self
+    }
+
+    /// Устанавливает интервал, с которым команда логирует через логгер контекста,
+    /// что она еще выполняется
+    pub fn heartbeat(mut self, interval: Duration) -> Self {
+        self.heartbeat = Some(interval);
+        self
+    }
+
+    /// Сохраняет вывод команды в переменную, доступную в команде отката и в следующих командах
+    pub fn capture_as(mut self, name: &str) -> Self {
+        self.capture_as = Some(name.to_string());
+        self
+    }
+
+    /// Устанавливает интерпретатор и флаг для передачи ему командной строки (например, `bash -c`)
+    pub fn shell(mut self, program: &str, flag: &str) -> Self {
+        self.shell = Some((program.to_string(), flag.to_string()));
+        self
+    }
+
+    /// Выполняет команду через PowerShell (`pwsh` или `powershell`)
+    pub fn powershell(self) -> Self {
+        self.shell(crate::command::process::powershell_program(), "-Command")
+    }
+
+    /// Сохраняет стандартный вывод без декодирования в `CommandResult::stdout_bytes`
+    pub fn binary_output(mut self, binary: bool) -> Self {
+        self.binary_output = binary;
+        self
+    }
+
+    /// Повторяет команду с интервалом, пока ее вывод не совпадет с шаблоном
+    pub fn wait_until(mut self, pattern: Regex, interval: Duration, max_attempts: u32) -> Self {
+        self.wait_until = Some((pattern, interval, max_attempts));
+        self
+    }
+
+    /// Требует, чтобы вывод успешной команды содержал подстроку
+    pub fn expect_output_contains(mut self, expected: &str) -> Self {
+        self.expect_output_contains = Some(expected.to_string());
+        self
+    }
+
+    /// Требует, чтобы вывод успешной команды совпадал с шаблоном
+    pub fn expect_output_matches(mut self, pattern: Regex) -> Self {
+        self.expect_output_matches = Some(pattern);
+        self
+    }
+
+    /// Ограничивает размер сохраняемого вывода каждого потока в байтах
+    pub fn max_output_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_output_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Устанавливает имена команд, после успешного выполнения которых запускается команда
+    ///
+    /// Учитывается цепочкой в режиме `ChainExecutionMode::Dag`.
+    pub fn depends_on(mut self, names: &[&str]) -> Self {
+        self.depends_on = names.iter().map(|name| name.to_string()).collect();
+        self
+    }
+
+    /// Строит по команде для каждого элемента списка
+    ///
+    /// Элемент подставляется вместо `{placeholder}` в командной строке и команде
+    /// отката (допускается передавать имя как с фигурными скобками, так и без них).
+    /// Имена и идентификаторы команд дополняются суффиксом `_<элемент>`.
+    pub fn for_each(self, items: Vec<String>, placeholder: &str) -> Vec<ShellCommand> {
+        let pattern = format!("{{{}}}", placeholder.trim_matches(['{', '}']));
+
+        items
+            .iter()
+            .map(|item| {
+                let mut builder = self.clone();
+                builder.name = format!("{}_{}", self.name, item);
+                builder.command = self.command.replace(&pattern, item);
+                builder.rollback_command = self
+                    .rollback_command
+                    .as_ref()
+                    .map(|command| command.replace(&pattern, item));
+                builder.on_error = self
+                    .on_error
+                    .as_ref()
+                    .map(|command| command.replace(&pattern, item));
+                builder.id = self.id.as_ref().map(|id| format!("{}_{}", id, item));
+                builder.build()
+            })
+            .collect()
+    }
+
+    /// Строит команду, предварительно проверив подстановки переменных
+    ///
+    /// Возвращает ошибку при несбалансированных фигурных скобках или пустой
+    /// подстановке (см. `ShellCommand::validate`).
+    pub fn try_build(self) -> Result<ShellCommand, BuildError> {
+        let command = self.build();
+
+        match command.placeholder_problem() {
+            Some(problem) => Err(BuildError::InvalidCommand(problem)),
+            None => Ok(command),
+        }
+    }
+
     /// Строит команду
     pub fn build(self) -> ShellCommand {
-        let mut command =
-            ShellCommand::new(&self.name, &self.command).with_execution_mode(self.mode);
+        let mut command = ShellCommand::new(&self.name, &self.command)
+            .with_execution_mode(self.mode)
+            .with_clean_env(self.clean_env)
+            .with_create_working_dir(self.create_working_dir)
+            .with_echo(self.echo)
+            .with_redact_variables(self.redact_variables)
+            .with_sandbox_optional(self.sandbox_optional)
+            .with_skip_rollback(self.skip_rollback)
+            .with_binary_output(self.binary_output)
+            .with_rollback_retries(self.rollback_retries)
+            .with_priority(self.priority)
+            .with_windows_env_expansion(self.windows_env_expansion);
 
         if let Some(dir) = self.working_dir {
             command = command.with_working_dir(&dir);
@@ -93,18 +473,99 @@ impl CommandBuilder {
             command = command.with_env_var(&key, &value);
         }
 
+        for key in self.env_remove {
+            command = command.with_env_remove(&key);
+        }
+
+        if let Some(id) = self.id {
+            command = command.with_id(&id);
+        }
+
+        for (key, value) in self.tags {
+            command = command.with_tag(&key, &value);
+        }
+
+        if let Some(path) = self.env_file {
+            command = command.with_env_file(&path);
+        }
+
         if let Some(rollback_cmd) = self.rollback_command {
             command = command.with_rollback(&rollback_cmd);
         }
 
-        if let Some(timeout) = self.timeout_seconds {
-            command = command.with_timeout(timeout);
+        if let Some(policy) = self.rollback_retry_policy {
+            command = command.with_rollback_retry_policy(policy);
+        }
+
+        if let Some(on_error) = self.on_error {
+            command = command.with_on_error(&on_error);
+        }
+
+        if let Some(timeout) = self.timeout {
+            command = command.with_timeout_duration(timeout);
+        }
+
+        if let Some(grace) = self.kill_grace {
+            command = command.with_kill_grace(grace);
         }
 
         if let Some(vars_file) = self.variables_file {
             command = command.with_variables_file(&vars_file);
         }
 
+        if let Some(parser) = self.output_parser {
+            command = command.with_parser(parser);
+        }
+
+        if let Some(mask) = self.umask {
+            command = command.with_umask(mask);
+        }
+
+        if let Some(codes) = self.success_codes {
+            command = command.with_success_codes(codes);
+        }
+
+        if let Some(sandbox) = self.sandbox {
+            command = command.with_sandbox(sandbox);
+        }
+
+        if let Some(data) = self.stdin {
+            command = command.with_stdin(data);
+        }
+
+        if let Some(interval) = self.heartbeat {
+            command = command.with_heartbeat(interval);
+        }
+
+        if let Some(name) = self.capture_as {
+            command = command.with_capture_as(&name);
+        }
+
+        if let Some((program, flag)) = self.shell {
+            command = command.with_shell(&program, &flag);
+        }
+
+        if let Some(max_bytes) = self.max_output_bytes {
+            command = command.with_max_output_bytes(max_bytes);
+        }
+
+        if let Some(expected) = self.expect_output_contains {
+            command = command.with_expect_output_contains(&expected);
+        }
+
+        if let Some(pattern) = self.expect_output_matches {
+            command = command.with_expect_output_matches(pattern);
+        }
+
+        if let Some((pattern, interval, max_attempts)) = self.wait_until {
+            command = command.with_wait_until(pattern, interval, max_attempts);
+        }
+
+        if !self.depends_on.is_empty() {
+            let names: Vec<&str> = self.depends_on.iter().map(String::as_str).collect();
+            command = command.with_depends_on(&names);
+        }
+
         command
     }
 }
@@ -0,0 +1,102 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::command::{Command, CompositeCommand, ExecutionMode};
+
+/// Строитель для составной команды (паттерн Строитель)
+pub struct CompositeCommandBuilder {
+    /// Название составной команды
+    name: String,
+
+    /// Режим выполнения
+    mode: ExecutionMode,
+
+    /// Откатывать ли выполненные подкоманды в случае ошибки
+    rollback_on_error: bool,
+
+    /// Общий таймаут выполнения подкоманд
+    timeout: Option<Duration>,
+
+    /// Максимальное число одновременно выполняемых подкоманд
+    max_concurrency: Option<usize>,
+
+    /// Вложенные команды
+    commands: Vec<Arc<dyn Command>>,
+}
+
+impl CompositeCommandBuilder {
+    /// Создает новый строитель составной команды
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            mode: ExecutionMode::Sequential,
+            rollback_on_error: false,
+            timeout: None,
+            max_concurrency: None,
+            commands: Vec::new(),
+        }
+    }
+
+    /// Устанавливает режим выполнения
+    pub fn execution_mode(mut self, mode: ExecutionMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Устанавливает флаг отката выполненных подкоманд при ошибке
+    pub fn rollback_on_error(mut self, rollback: bool) -> Self {
+        self.rollback_on_error = rollback;
+        self
+    }
+
+    /// Устанавливает общий таймаут выполнения подкоманд
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Ограничивает число одновременно выполняемых подкоманд в параллельном режиме
+    pub fn max_concurrency(mut self, limit: usize) -> Self {
+        self.max_concurrency = Some(limit);
+        self
+    }
+
+    /// Добавляет команду в группу
+    pub fn command<C: Command + 'static>(mut self, command: C) -> Self {
+        self.commands.push(Arc::new(command));
+        self
+    }
+
+    /// Создает составную команду
+    pub fn build(self) -> CompositeCommand {
+        let mut composite = CompositeCommand::new(&self.name);
+        composite
+            .with_execution_mode(self.mode)
+            .with_rollback_on_error(self.rollback_on_error);
+
+        if let Some(timeout) = self.timeout {
+            composite.with_timeout(timeout);
+        }
+
+        if let Some(limit) = self.max_concurrency {
+            composite.with_max_concurrency(limit);
+        }
+
+        for command in self.commands {
+            composite.push_command(command);
+        }
+
+        composite
+    }
+
+    /// Создает составную команду с указанными командами
+    pub fn build_with_commands<C>(self, commands: Vec<C>) -> CompositeCommand
+    where
+        C: Command + 'static,
+    {
+        commands
+            .into_iter()
+            .fold(self, |builder, command| builder.command(command))
+            .build()
+    }
+}
@@ -0,0 +1,20 @@
+use thiserror::Error;
+
+/// Ошибки проверки конфигурации при построении команд и цепочек
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum BuildError {
+    #[error("Название цепочки не может быть пустым")]
+    EmptyName,
+
+    #[error("Имя команды '{0}' повторяется в цепочке")]
+    DuplicateCommand(String),
+
+    #[error("Команда '{command}' зависит от неизвестной команды '{dependency}'")]
+    UnknownDependency { command: String, dependency: String },
+
+    #[error("Обнаружен цикл зависимостей: {}", .0.join(", "))]
+    DependencyCycle(Vec<String>),
+
+    #[error("{0}")]
+    InvalidCommand(String),
+}
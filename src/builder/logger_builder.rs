@@ -0,0 +1,59 @@
+use std::sync::Arc;
+
+use crate::logging::{CompositeLogger, ConsoleLogger, FileLogger, LogLevel, Logger, NullLogger};
+
+/// Строитель логгера, объединяющего несколько приемников (паттерн Строитель)
+#[derive(Default)]
+pub struct LoggerBuilder {
+    /// Добавленные логгеры
+    loggers: Vec<Box<dyn Logger>>,
+}
+
+impl LoggerBuilder {
+    /// Создает строитель без логгеров
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Добавляет консольный логгер
+    pub fn console(self, level: LogLevel) -> Self {
+        self.logger(Box::new(ConsoleLogger::new(level)))
+    }
+
+    /// Добавляет консольный логгер, выводящий JSON-строки (см. `ConsoleLogger::json`)
+    pub fn json_console(self, level: LogLevel) -> Self {
+        self.logger(Box::new(ConsoleLogger::json(level)))
+    }
+
+    /// Добавляет файловый логгер
+    pub fn file(self, level: LogLevel, path: &str) -> Self {
+        self.logger(Box::new(FileLogger::new(level, path)))
+    }
+
+    /// Добавляет произвольный логгер
+    pub fn logger(mut self, logger: Box<dyn Logger>) -> Self {
+        self.loggers.push(logger);
+        self
+    }
+
+    /// Создает логгер
+    ///
+    /// Единственный добавленный логгер возвращается как есть, несколько
+    /// объединяются в `CompositeLogger`, без логгеров возвращается `NullLogger`.
+    /// Результат можно передать в `CommandChain::with_logger`.
+    pub fn build(mut self) -> Box<dyn Logger> {
+        match self.loggers.len() {
+            0 => Box::new(NullLogger::new()),
+            1 => self.loggers.remove(0),
+            _ => Box::new(CompositeLogger::with_loggers(self.loggers)),
+        }
+    }
+
+    /// Создает логгер для совместного использования несколькими цепочками
+    ///
+    /// Каждой цепочке передается своя копия указателя:
+    /// `chain.with_logger(Box::new(Arc::clone(&logger)))`.
+    pub fn build_shared(self) -> Arc<Box<dyn Logger>> {
+        Arc::new(self.build())
+    }
+}
@@ -1,5 +1,11 @@
 pub mod chain_builder;
 pub mod command_builder;
+pub mod composite_builder;
+pub mod error;
+pub mod logger_builder;
 
 pub use chain_builder::ChainBuilder;
 pub use command_builder::CommandBuilder;
+pub use composite_builder::CompositeCommandBuilder;
+pub use error::BuildError;
+pub use logger_builder::LoggerBuilder;
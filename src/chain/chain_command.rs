@@ -0,0 +1,171 @@
+use async_trait::async_trait;
+use std::sync::Arc;
+
+use crate::chain::command_chain::{CommandChain, ExecutedCommands};
+use crate::command::traits::{
+    Command, CommandError, CommandExecution, CommandResult, ExecutionMode, RollbackState,
+};
+use crate::command::ExecutionContext;
+use crate::visitor::Visitor;
+
+/// Команда, выполняющая вложенную цепочку команд
+///
+/// Результат вложенной цепочки сводится к одному результату команды:
+/// вывод команд объединяется, а ошибки перечисляются через `; `.
+/// Выполненные команды вложенной цепочки сохраняются в результате запуска,
+/// поэтому при откате внешней цепочки откатываются команды именно этого запуска.
+pub struct ChainCommand {
+    /// Вложенная цепочка
+    chain: Arc<CommandChain>,
+}
+
+impl ChainCommand {
+    /// Создает команду для вложенной цепочки
+    pub fn new(chain: Arc<CommandChain>) -> Self {
+        Self { chain }
+    }
+
+    /// Возвращает вложенную цепочку
+    pub fn chain(&self) -> &Arc<CommandChain> {
+        &self.chain
+    }
+}
+
+impl std::fmt::Debug for ChainCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChainCommand")
+            .field("name", &self.chain.name())
+            .field("commands", &self.chain.commands().len())
+            .finish()
+    }
+}
+
+#[async_trait]
+impl CommandExecution for ChainCommand {
+    async fn execute(&self) -> Result<CommandResult, CommandError> {
+        self.execute_with_context(&ExecutionContext::default())
+            .await
+    }
+
+    async fn execute_with_context(
+        &self,
+        context: &ExecutionContext<'_>,
+    ) -> Result<CommandResult, CommandError> {
+        let result = CommandResult::new(self.name());
+        let (chain_result, executed) = self.chain.execute_nested(context).await;
+
+        let chain_result = chain_result?;
+
+        // Выполненные команды сохраняются в результате, чтобы откатить их при откате внешней цепочки
+        let result = result.with_rollback_state(RollbackState {
+            executed,
+            ..RollbackState::default()
+        });
+
+        for pid in &chain_result.pids {
+            context.record_pid(*pid);
+        }
+
+        let mut all_output = String::new();
+        for cmd_result in &chain_result.results {
+            all_output.push_str(&format!(
+                "{}:\n{}\n",
+                cmd_result.command_name, cmd_result.output
+            ));
+        }
+
+        let result = result.with_data(serde_json::to_value(&chain_result.results).ok());
+
+        if chain_result.success {
+            Ok(result.success(all_output))
+        } else {
+            let mut result = result.failure(chain_result.errors.join("; "), None);
+            result.output = all_output;
+            Ok(result)
+        }
+    }
+
+    async fn rollback(&self) -> Result<CommandResult, CommandError> {
+        self.rollback_with_context(&ExecutionContext::new()).await
+    }
+
+    /// Откатывает команды вложенной цепочки
+    ///
+    /// Откатываются команды, выполненные при запуске, результат которого передан
+    /// в `context.forward_result`. Без результата запуска откатываются все команды
+    /// вложенной цепочки, поддерживающие откат.
+    async fn rollback_with_context(
+        &self,
+        context: &ExecutionContext<'_>,
+    ) -> Result<CommandResult, CommandError> {
+        let executed: ExecutedCommands = match context
+            .forward_result
+            .and_then(CommandResult::rollback_state)
+        {
+            Some(state) => state.executed.clone(),
+            None => self
+                .chain
+                .commands()
+                .iter()
+                .map(|command| (Arc::clone(command), CommandResult::new(command.name())))
+                .collect(),
+        };
+
+        let rollback_results = self.chain.rollback_executed(executed).await;
+        let result =
+            CommandResult::new(self.name()).with_data(serde_json::to_value(&rollback_results).ok());
+
+        let errors: Vec<String> = rollback_results
+            .iter()
+            .filter(|result| !result.success)
+            .map(|result| {
+                format!(
+                    "{}: {}",
+                    result.command_name,
+                    result.error.as_deref().unwrap_or("<неизвестная ошибка>")
+                )
+            })
+            .collect();
+
+        if errors.is_empty() {
+            Ok(result.success(format!(
+                "Откат вложенной цепочки '{}' выполнен",
+                self.name()
+            )))
+        } else {
+            Ok(result.failure(errors.join("; "), None))
+        }
+    }
+
+    fn name(&self) -> &str {
+        self.chain.name()
+    }
+
+    fn execution_mode(&self) -> ExecutionMode {
+        ExecutionMode::Sequential
+    }
+
+    fn rollback_preview(&self) -> Option<String> {
+        let preview = self.chain.preview_rollback();
+
+        (!preview.is_empty()).then(|| preview.join("; "))
+    }
+
+    fn supports_rollback(&self) -> bool {
+        self.chain
+            .commands()
+            .iter()
+            .any(|command| command.supports_rollback())
+    }
+}
+
+#[async_trait]
+impl Command for ChainCommand {
+    fn accept(&self, visitor: &mut dyn Visitor) {
+        visitor.visit_chain_command(self);
+
+        for command in self.chain.commands() {
+            command.accept(visitor);
+        }
+    }
+}
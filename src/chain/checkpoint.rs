@@ -0,0 +1,133 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::command::traits::CommandError;
+
+/// Успешно выполненная команда цепочки
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub(crate) struct CompletedCommand {
+    /// Позиция команды в цепочке
+    pub index: usize,
+
+    /// Имя команды
+    pub name: String,
+}
+
+/// Содержимое файла контрольной точки
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct CheckpointData {
+    /// Успешно выполненные команды в порядке выполнения
+    completed: Vec<CompletedCommand>,
+
+    /// Переменные, захваченные из вывода выполненных команд
+    #[serde(default)]
+    variables: HashMap<String, String>,
+}
+
+/// Контрольная точка возобновляемого запуска цепочки
+///
+/// Хранит в JSON-файле позиции и имена успешно выполненных команд, а также
+/// захваченные ими переменные, чтобы после прерывания запуск можно было
+/// продолжить с первой невыполненной команды. Позиция сохраняется, поскольку
+/// имена команд в цепочке могут повторяться.
+#[derive(Debug)]
+pub(crate) struct Checkpoint {
+    /// Путь к файлу контрольной точки
+    path: PathBuf,
+
+    /// Сохраненное состояние
+    data: Mutex<CheckpointData>,
+}
+
+impl Checkpoint {
+    /// Загружает контрольную точку из файла (пустую, если файла нет)
+    pub fn load(path: &str) -> Result<Self, CommandError> {
+        let path = PathBuf::from(path);
+
+        let data = if path.exists() {
+            let contents = std::fs::read_to_string(&path).map_err(|e| {
+                CommandError::ExecutionError(format!(
+                    "Не удалось прочитать контрольную точку {}: {}",
+                    path.display(),
+                    e
+                ))
+            })?;
+
+            serde_json::from_str(&contents).map_err(|e| {
+                CommandError::ExecutionError(format!(
+                    "Не удалось разобрать контрольную точку {}: {}",
+                    path.display(),
+                    e
+                ))
+            })?
+        } else {
+            CheckpointData::default()
+        };
+
+        Ok(Self {
+            path,
+            data: Mutex::new(data),
+        })
+    }
+
+    /// Возвращает команды, выполненные в предыдущих запусках
+    pub fn completed(&self) -> HashSet<CompletedCommand> {
+        self.lock().completed.iter().cloned().collect()
+    }
+
+    /// Возвращает переменные, захваченные командами предыдущих запусков
+    pub fn variables(&self) -> HashMap<String, String> {
+        self.lock().variables.clone()
+    }
+
+    /// Отмечает команду как выполненную, сохраняя захваченные ею переменные
+    pub fn record(
+        &self,
+        index: usize,
+        name: &str,
+        captured: &HashMap<String, String>,
+    ) -> Result<(), CommandError> {
+        let mut data = self.lock();
+        data.completed.push(CompletedCommand {
+            index,
+            name: name.to_string(),
+        });
+        data.variables.extend(captured.clone());
+
+        let contents = serde_json::to_string(&*data).map_err(|e| {
+            CommandError::ExecutionError(format!(
+                "Не удалось сериализовать контрольную точку: {}",
+                e
+            ))
+        })?;
+
+        // Файл заменяется целиком, чтобы сбой во время записи не повредил контрольную точку
+        let temp_path = Self::temp_path(&self.path);
+        std::fs::write(&temp_path, contents)?;
+        std::fs::rename(&temp_path, &self.path)?;
+
+        Ok(())
+    }
+
+    /// Удаляет файл контрольной точки после завершения цепочки
+    pub fn remove(&self) -> Result<(), CommandError> {
+        match std::fs::remove_file(&self.path) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Блокирует сохраненное состояние
+    fn lock(&self) -> std::sync::MutexGuard<'_, CheckpointData> {
+        self.data.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Возвращает путь временного файла для записи контрольной точки
+    fn temp_path(path: &Path) -> PathBuf {
+        let mut name = path.file_name().unwrap_or_default().to_os_string();
+        name.push(".tmp");
+        path.with_file_name(name)
+    }
+}
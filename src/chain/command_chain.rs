@@ -1,10 +1,25 @@
-use futures::future;
+use futures::channel::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use futures::stream::{FuturesUnordered, StreamExt};
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use serde_json::json;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
 
+use crate::chain::checkpoint::{Checkpoint, CompletedCommand};
+use crate::chain::config::ChainConfig;
+use crate::chain::events::ChainEvent;
+use crate::chain::graph::DependencyGraph;
+use crate::command::runtime;
 use crate::command::traits::CommandError;
-use crate::command::{Command, CommandResult, ExecutionMode};
-use crate::logging::{LogLevel, Logger};
+use crate::command::{
+    Command, CommandResult, ExecutionContext, ExecutionMode, ShellCommand, VariableCache,
+};
+use crate::logging::{LogContext, LogLevel, Logger};
 use crate::visitor::LogVisitor;
 
 /// Режим выполнения цепочки команд
@@ -16,10 +31,13 @@ pub enum ChainExecutionMode {
     Parallel,
     /// Автоматический выбор режима на основе флагов команд
     Auto,
+    /// Выполнение по графу зависимостей: независимые команды выполняются параллельно,
+    /// зависимые - после успешного выполнения своих зависимостей
+    Dag,
 }
 
 /// Результат выполнения цепочки команд
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChainResult {
     /// Результаты отдельных команд
     pub results: Vec<CommandResult>,
@@ -27,8 +45,316 @@ pub struct ChainResult {
     /// Общий результат (успех/неудача)
     pub success: bool,
 
-    /// Сообщение об ошибке (если есть)
+    /// Сообщение об ошибке (если есть), первое из `errors`
     pub error: Option<String>,
+
+    /// Сообщения об ошибках всех неудачно выполненных команд
+    #[serde(default)]
+    pub errors: Vec<String>,
+
+    /// Идентификаторы всех процессов, запущенных цепочкой
+    #[serde(default)]
+    pub pids: Vec<u32>,
+
+    /// Режим, в котором фактически выполнялась цепочка
+    ///
+    /// Для `ChainExecutionMode::Auto` показывает выбранный режим, для
+    /// выполнения по графу зависимостей - `Parallel`.
+    #[serde(default)]
+    pub chosen_mode: ExecutionMode,
+
+    /// Выполнялся ли откат выполненных команд
+    #[serde(default)]
+    pub rolled_back: bool,
+
+    /// Результаты команд отката в порядке их выполнения
+    #[serde(default)]
+    pub rollback_results: Vec<CommandResult>,
+}
+
+impl ChainResult {
+    /// Возвращает отчет о выполнении цепочки в формате JSON
+    pub fn to_json(&self) -> String {
+        // Сериализация не может завершиться ошибкой: все поля представимы в JSON
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    /// Возвращает сводную статистику выполнения цепочки
+    pub fn summary(&self) -> ChainSummary {
+        let total_duration_ms = self.results.iter().map(|r| r.duration_ms).sum();
+
+        // Реальное время - от начала первой до завершения последней команды,
+        // поэтому для параллельного выполнения оно меньше суммы длительностей
+        let started = self.results.iter().map(|r| r.start_time).min();
+        let finished = self.results.iter().map(|r| r.end_time).max();
+        let wall_clock_ms = match (started, finished) {
+            (Some(started), Some(finished)) => {
+                (finished - started).num_milliseconds().max(0) as u64
+            }
+            _ => 0,
+        };
+
+        let slowest_command = self
+            .results
+            .iter()
+            .filter(|r| !r.is_skipped())
+            .max_by_key(|r| r.duration_ms)
+            .map(|r| r.command_name.clone());
+
+        ChainSummary {
+            total_duration_ms,
+            wall_clock_ms,
+            succeeded: self
+                .results
+                .iter()
+                .filter(|r| r.success && !r.is_skipped())
+                .count(),
+            failed: self.results.iter().filter(|r| !r.success).count(),
+            skipped: self.results.iter().filter(|r| r.is_skipped()).count(),
+            slowest_command,
+            output_bytes: self.results.iter().map(|r| r.output.len()).sum(),
+        }
+    }
+}
+
+impl fmt::Display for ChainResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = if self.success {
+            "успешно"
+        } else {
+            "ОШИБКА"
+        };
+        writeln!(f, "Цепочка: {} (команд: {})", status, self.results.len())?;
+
+        for result in &self.results {
+            let status = if result.is_skipped() {
+                "ПРОПУЩЕНА"
+            } else if result.success {
+                "OK"
+            } else {
+                "ОШИБКА"
+            };
+
+            // В строке команды показывается только первая строка вывода или ошибки
+            let details = result.to_string();
+            let details = details.lines().next().unwrap_or_default();
+
+            writeln!(f, "  [{}] {}: {}", status, result.command_name, details)?;
+        }
+
+        if !self.errors.is_empty() {
+            writeln!(f, "Ошибки:")?;
+            for error in &self.errors {
+                writeln!(f, "  - {}", error)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Сводная статистика выполнения цепочки
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainSummary {
+    /// Сумма длительностей всех команд в миллисекундах
+    pub total_duration_ms: u64,
+
+    /// Реальное время выполнения команд в миллисекундах
+    pub wall_clock_ms: u64,
+
+    /// Количество успешно выполненных команд
+    pub succeeded: usize,
+
+    /// Количество команд, завершившихся с ошибкой
+    pub failed: usize,
+
+    /// Количество пропущенных команд
+    pub skipped: usize,
+
+    /// Имя самой долгой команды (если выполнялась хотя бы одна команда)
+    pub slowest_command: Option<String>,
+
+    /// Общий размер вывода команд в байтах
+    pub output_bytes: usize,
+}
+
+/// Обработчик, вызываемый перед выполнением каждой команды цепочки (получает имя команды)
+pub type BeforeHook = Box<dyn Fn(&str) + Send + Sync>;
+
+/// Обработчик, вызываемый после выполнения каждой команды цепочки (получает имя и результат)
+pub type AfterHook = Box<dyn Fn(&str, &CommandResult) + Send + Sync>;
+
+/// Обработчик хода выполнения цепочки (получает число завершенных команд и общее число команд)
+pub type ProgressHook = Box<dyn Fn(usize, usize) + Send + Sync>;
+
+/// Выполненные команды запуска цепочки вместе с их результатами
+pub(crate) type ExecutedCommands = Vec<(Arc<dyn Command>, CommandResult)>;
+
+/// Причина прерывания выполнения цепочки
+enum Interruption {
+    /// Истек общий таймаут цепочки
+    Timeout(Duration),
+    /// Выполнение отменено через токен
+    Cancelled,
+}
+
+/// Состояние одного запуска цепочки
+struct RunState {
+    /// Время начала запуска
+    started: Instant,
+
+    /// Идентификаторы запущенных процессов
+    pids: Mutex<Vec<u32>>,
+
+    /// Канал для отправки событий выполнения
+    events: Option<UnboundedSender<ChainEvent>>,
+
+    /// Выполненные команды и их результаты (для отката)
+    executed: Mutex<ExecutedCommands>,
+
+    /// Режим пробного запуска, заданный извне (например, внешней цепочкой)
+    dry_run: bool,
+
+    /// Токен отмены выполнения
+    cancel: Option<CancellationToken>,
+
+    /// Переменные окружения цепочки, объединенные с окружением внешней цепочки
+    env: Option<HashMap<String, String>>,
+
+    /// Значения переменных, определенные командами запуска
+    variables: VariableCache,
+
+    /// Число завершенных команд запуска
+    completed: AtomicUsize,
+
+    /// Режим выполнения запуска (вместо режима цепочки)
+    mode: Option<ChainExecutionMode>,
+
+    /// Выполнялся ли откат в этом запуске
+    rolled_back: AtomicBool,
+
+    /// Результаты команд отката
+    rollback_results: Mutex<Vec<CommandResult>>,
+
+    /// Контрольная точка возобновляемого запуска
+    checkpoint: Option<Checkpoint>,
+}
+
+impl RunState {
+    /// Создает состояние нового запуска
+    fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            pids: Mutex::new(Vec::new()),
+            events: None,
+            executed: Mutex::new(Vec::new()),
+            dry_run: false,
+            cancel: None,
+            env: None,
+            variables: VariableCache::new(),
+            completed: AtomicUsize::new(0),
+            mode: None,
+            rolled_back: AtomicBool::new(false),
+            rollback_results: Mutex::new(Vec::new()),
+            checkpoint: None,
+        }
+    }
+
+    /// Устанавливает контрольную точку возобновляемого запуска
+    fn with_checkpoint(mut self, checkpoint: Checkpoint) -> Self {
+        self.checkpoint = Some(checkpoint);
+        self
+    }
+
+    /// Устанавливает режим выполнения запуска
+    fn with_mode(mut self, mode: ChainExecutionMode) -> Self {
+        self.mode = Some(mode);
+        self
+    }
+
+    /// Устанавливает объединенные переменные окружения цепочки
+    fn with_env(mut self, env: HashMap<String, String>) -> Self {
+        self.env = Some(env);
+        self
+    }
+
+    /// Устанавливает токен отмены выполнения
+    fn with_cancel(mut self, token: CancellationToken) -> Self {
+        self.cancel = Some(token);
+        self
+    }
+
+    /// Ожидает отмены выполнения (никогда не завершается без токена)
+    async fn cancelled(&self) {
+        match &self.cancel {
+            Some(token) => token.cancelled().await,
+            None => std::future::pending().await,
+        }
+    }
+
+    /// Устанавливает режим пробного запуска
+    fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Устанавливает ранее выполненные команды (для отката)
+    fn with_executed(self, executed: ExecutedCommands) -> Self {
+        *self.executed.lock().unwrap_or_else(|e| e.into_inner()) = executed;
+        self
+    }
+
+    /// Устанавливает канал для отправки событий выполнения
+    fn with_events(mut self, events: UnboundedSender<ChainEvent>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Отправляет событие выполнения, если канал установлен
+    fn emit(&self, event: ChainEvent) {
+        if let Some(events) = &self.events {
+            // Получатель мог перестать читать события, это не ошибка выполнения
+            let _ = events.unbounded_send(event);
+        }
+    }
+
+    /// Сохраняет команду как выполненную (пропущенные команды не откатываются)
+    fn record_executed(&self, command: &Arc<dyn Command>, result: &CommandResult) {
+        if !result.is_skipped() {
+            self.executed
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .push((Arc::clone(command), result.clone()));
+        }
+    }
+
+    /// Забирает выполненные команды и их результаты
+    fn take_executed(&self) -> ExecutedCommands {
+        std::mem::take(&mut *self.executed.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    /// Сохраняет результат команды отката
+    fn record_rollback(&self, result: CommandResult) {
+        self.rollback_results
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(result);
+    }
+
+    /// Забирает результаты команд отката
+    fn take_rollback_results(&self) -> Vec<CommandResult> {
+        std::mem::take(
+            &mut *self
+                .rollback_results
+                .lock()
+                .unwrap_or_else(|e| e.into_inner()),
+        )
+    }
+
+    /// Забирает собранные идентификаторы процессов
+    fn take_pids(&self) -> Vec<u32> {
+        std::mem::take(&mut *self.pids.lock().unwrap_or_else(|e| e.into_inner()))
+    }
 }
 
 /// Цепочка команд (паттерн Цепочка Обязанностей)
@@ -47,6 +373,39 @@ pub struct CommandChain {
 
     /// Откатывать ли выполненные команды в случае ошибки
     rollback_on_error: bool,
+
+    /// Выводить ли команды перед выполнением
+    echo_commands: bool,
+
+    /// Режим пробного запуска (команды не выполняются)
+    dry_run: bool,
+
+    /// Общий таймаут выполнения цепочки
+    chain_timeout: Option<Duration>,
+
+    /// Обработчик, вызываемый перед выполнением каждой команды
+    before_each: Option<BeforeHook>,
+
+    /// Обработчик, вызываемый после выполнения каждой команды
+    after_each: Option<AfterHook>,
+
+    /// Обработчик хода выполнения, вызываемый после завершения каждой команды
+    on_progress: Option<ProgressHook>,
+
+    /// Переменные окружения, применяемые ко всем командам цепочки
+    env_vars: HashMap<String, String>,
+
+    /// Разделять ли значения переменных между командами цепочки
+    share_variables: bool,
+
+    /// Таймаут для команд цепочки, для которых он не задан явно
+    default_timeout: Option<Duration>,
+
+    /// Значения переменных, заданные явно (имеют приоритет над файлом и окружением)
+    overrides: HashMap<String, String>,
+
+    /// Записывать ли итоговую строку в лог по завершении запуска
+    log_summary: bool,
 }
 
 impl CommandChain {
@@ -58,30 +417,72 @@ impl CommandChain {
             mode: ChainExecutionMode::Sequential,
             logger: None,
             rollback_on_error: true,
+            echo_commands: false,
+            dry_run: false,
+            chain_timeout: None,
+            before_each: None,
+            after_each: None,
+            on_progress: None,
+            env_vars: HashMap::new(),
+            share_variables: false,
+            default_timeout: None,
+            overrides: HashMap::new(),
+            log_summary: true,
         }
     }
 
+    /// Возвращает название цепочки
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Возвращает команды цепочки
+    pub(crate) fn commands(&self) -> &[Arc<dyn Command>] {
+        &self.commands
+    }
+
+    /// Загружает цепочку команд из конфигурационного файла (YAML или JSON)
+    pub fn from_config_file(path: &str) -> Result<Self, CommandError> {
+        Ok(ChainConfig::from_file(path)?.build())
+    }
+
     /// Добавляет команду в цепочку
     pub fn add_command<C: Command + 'static>(&mut self, command: C) -> &mut Self {
+        self.add_shared_command(Arc::new(command))
+    }
+
+    /// Добавляет в цепочку shell-команду с настройками по умолчанию
+    ///
+    /// Сокращение для `add_command(ShellCommand::new(name, command))`.
+    pub fn add_shell(&mut self, name: &str, command: &str) -> &mut Self {
+        self.add_command(ShellCommand::new(name, command))
+    }
+
+    /// Добавляет в цепочку команду, уже обернутую в `Arc`
+    ///
+    /// Позволяет использовать один экземпляр команды в нескольких цепочках.
+    pub fn add_shared_command(&mut self, command: Arc<dyn Command>) -> &mut Self {
         // Логируем добавление команды, если логгер установлен
-        if let Some(logger) = &self.logger {
-            logger.info(&format!(
+        self.log_command(
+            LogLevel::Info,
+            command.name(),
+            &format!(
                 "Добавлена команда '{}' в цепочку '{}'",
                 command.name(),
                 self.name
-            ));
-        }
+            ),
+        );
 
         // Создаем визитор для логирования, если логгер установлен
         if let Some(logger) = &self.logger {
-            let mut visitor = LogVisitor::new(logger, LogLevel::Debug);
+            let mut visitor = LogVisitor::new(logger.as_ref(), LogLevel::Debug);
 
             // Применяем визитор к команде
             command.accept(&mut visitor);
         }
 
         // Добавляем команду в список
-        self.commands.push(Arc::new(command));
+        self.commands.push(command);
         self
     }
 
@@ -90,12 +491,13 @@ impl CommandChain {
         self.mode = mode;
 
         // Логируем изменение режима, если логгер установлен
-        if let Some(logger) = &self.logger {
-            logger.info(&format!(
+        self.log_chain(
+            LogLevel::Info,
+            &format!(
                 "Установлен режим выполнения цепочки '{}': {:?}",
                 self.name, mode
-            ));
-        }
+            ),
+        );
 
         self
     }
@@ -111,22 +513,365 @@ impl CommandChain {
         self.rollback_on_error = rollback;
 
         // Логируем изменение флага отката, если логгер установлен
-        if let Some(logger) = &self.logger {
-            logger.info(&format!(
+        self.log_chain(
+            LogLevel::Info,
+            &format!(
                 "Установлен флаг отката при ошибке для цепочки '{}': {}",
                 self.name, rollback
-            ));
-        }
+            ),
+        );
+
+        self
+    }
+
+    /// Устанавливает флаг вывода команд перед выполнением
+    ///
+    /// Строки вывода команд при этом передаются логгеру цепочки (`Logger::log_output`).
+    pub fn with_echo_commands(&mut self, echo: bool) -> &mut Self {
+        self.echo_commands = echo;
+        self
+    }
+
+    /// Устанавливает режим пробного запуска
+    pub fn with_dry_run(&mut self, dry_run: bool) -> &mut Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Устанавливает запись итоговой строки в лог по завершении запуска (по умолчанию включена)
+    ///
+    /// Итоговая строка записывается с уровнем `Info` (или `Error` при неудаче) и
+    /// контекстом, содержащим имя цепочки, число команд, успешных, неудачных и
+    /// пропущенных команд, длительность запуска и признак выполненного отката.
+    pub fn with_log_summary(&mut self, enabled: bool) -> &mut Self {
+        self.log_summary = enabled;
+        self
+    }
+
+    /// Добавляет переменную окружения для всех команд цепочки
+    ///
+    /// Переменные, заданные в самой команде, имеют приоритет.
+    pub fn with_env_var(&mut self, key: &str, value: &str) -> &mut Self {
+        self.env_vars.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    /// Устанавливает разделение значений переменных между командами цепочки
+    ///
+    /// Значение переменной, запрошенное у пользователя (или полученное из файла
+    /// или окружения) одной командой, используется остальными командами запуска
+    /// без повторного запроса.
+    pub fn with_share_variables(&mut self, share: bool) -> &mut Self {
+        self.share_variables = share;
+        self
+    }
+
+    /// Задает значения переменных, имеющие наивысший приоритет
+    ///
+    /// Значения подставляются вместо `{name}`, `{#name}`, `{$name}` и `{!name}`
+    /// без обращения к файлу переменных, окружению или пользователю (аналог
+    /// `-e KEY=VAL` в утилитах командной строки). Разделение значений между
+    /// командами (`with_share_variables`) при этом не включается.
+    pub fn with_overrides(&mut self, overrides: HashMap<String, String>) -> &mut Self {
+        self.overrides.extend(overrides);
+        self
+    }
+
+    /// Возвращает true, если команды запуска используют общий кэш переменных
+    fn uses_variable_cache(&self) -> bool {
+        self.share_variables
+    }
 
+    /// Устанавливает таймаут для команд цепочки, для которых он не задан явно
+    ///
+    /// В отличие от `with_chain_timeout` ограничивает время выполнения каждой
+    /// команды (и команды отката) в отдельности.
+    pub fn with_default_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.default_timeout = Some(timeout);
         self
     }
 
+    /// Устанавливает общий таймаут выполнения цепочки
+    ///
+    /// По истечении таймаута выполняемые команды прерываются, а выполненные
+    /// откатываются (если включен откат при ошибке).
+    pub fn with_chain_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.chain_timeout = Some(timeout);
+        self
+    }
+
+    /// Устанавливает обработчик, вызываемый перед выполнением каждой команды
+    pub fn with_before_each(&mut self, hook: BeforeHook) -> &mut Self {
+        self.before_each = Some(hook);
+        self
+    }
+
+    /// Устанавливает обработчик, вызываемый после выполнения каждой команды
+    ///
+    /// При критической ошибке выполнения обработчик получает результат с описанием ошибки.
+    pub fn with_after_each(&mut self, hook: AfterHook) -> &mut Self {
+        self.after_each = Some(hook);
+        self
+    }
+
+    /// Устанавливает обработчик хода выполнения
+    ///
+    /// Вызывается после завершения каждой команды (в параллельном режиме - в
+    /// порядке завершения) с числом завершенных команд запуска и общим числом
+    /// команд цепочки. Команды, не запущенные из-за ошибки, не учитываются.
+    pub fn with_on_progress(&mut self, hook: ProgressHook) -> &mut Self {
+        self.on_progress = Some(hook);
+        self
+    }
+
+    /// Создает контекст выполнения для команд цепочки
+    fn execution_context<'a>(&'a self, state: &'a RunState) -> ExecutionContext<'a> {
+        let mut context = ExecutionContext::new()
+            .with_echo(self.echo_commands)
+            .with_dry_run(self.dry_run || state.dry_run)
+            .with_pids(&state.pids)
+            .with_env(self.env(state))
+            .with_chain_name(&self.name);
+
+        if let Some(logger) = &self.logger {
+            context = context.with_logger(logger.as_ref());
+        }
+
+        if self.uses_variable_cache() {
+            context = context.with_variable_cache(&state.variables);
+        }
+
+        if !self.overrides.is_empty() {
+            context = context.with_overrides(&self.overrides);
+        }
+
+        if let Some(timeout) = self.default_timeout {
+            context = context.with_default_timeout(timeout);
+        }
+
+        context
+    }
+
+    /// Логирует сообщение цепочки с контекстом, содержащим имя цепочки
+    fn log_chain(&self, level: LogLevel, message: &str) {
+        if let Some(logger) = &self.logger {
+            let context = LogContext::new().with_extra(json!({ "chain": self.name }));
+            logger.log_with_context(level, message, &context);
+        }
+    }
+
+    /// Логирует сообщение о команде с контекстом, содержащим имена цепочки и команды
+    fn log_command(&self, level: LogLevel, command: &str, message: &str) {
+        if let Some(logger) = &self.logger {
+            let context = LogContext::new().with_extra(json!({
+                "chain": self.name,
+                "command": command,
+            }));
+            logger.log_with_context(level, message, &context);
+        }
+    }
+
+    /// Возвращает переменные окружения, применяемые к командам запуска
+    fn env<'a>(&'a self, state: &'a RunState) -> &'a HashMap<String, String> {
+        state.env.as_ref().unwrap_or(&self.env_vars)
+    }
+
+    /// Выполняет команду цепочки, учитывая время ожидания ее запуска
+    ///
+    /// `ready` - момент, когда команда стала готова к запуску (подошла ее очередь
+    /// или выполнены ее зависимости); время до фактического запуска записывается
+    /// в `queued_ms`.
+    async fn run_command(
+        &self,
+        command: &Arc<dyn Command>,
+        context: &ExecutionContext<'_>,
+        state: &RunState,
+        ready: Instant,
+    ) -> Result<CommandResult, CommandError> {
+        let queued_ms = ready.elapsed().as_millis() as u64;
+        let started = Instant::now();
+
+        state.emit(ChainEvent::CommandStarted {
+            name: command.name().to_string(),
+        });
+
+        if let Some(hook) = &self.before_each {
+            hook(command.name());
+        }
+
+        let result = command
+            .execute_with_context(context)
+            .await
+            .map(|mut result| {
+                result.queued_ms = queued_ms;
+                result
+            });
+
+        let (success, duration_ms) = match &result {
+            Ok(cmd_result) => (cmd_result.success, cmd_result.duration_ms),
+            Err(_) => (false, started.elapsed().as_millis() as u64),
+        };
+
+        state.emit(ChainEvent::CommandFinished {
+            name: command.name().to_string(),
+            success,
+            duration_ms,
+        });
+
+        if let Some(hook) = &self.after_each {
+            match &result {
+                Ok(cmd_result) => hook(command.name(), cmd_result),
+                Err(err) => hook(
+                    command.name(),
+                    &CommandResult::new(command.name()).failure(err.to_string(), None),
+                ),
+            }
+        }
+
+        if let Some(hook) = &self.on_progress {
+            let completed = state.completed.fetch_add(1, Ordering::SeqCst) + 1;
+            hook(completed, self.commands.len());
+        }
+
+        result
+    }
+
+    /// Возвращает описание отката команд цепочки без его выполнения
+    ///
+    /// Команды перечисляются в порядке отката (обратном порядку добавления)
+    /// с командами отката, в которых подставлены известные переменные.
+    pub fn preview_rollback(&self) -> Vec<String> {
+        self.commands
+            .iter()
+            .rev()
+            .map(|command| {
+                if command.skip_rollback() {
+                    format!("{}: исключена из отката", command.name())
+                } else {
+                    match command.rollback_preview() {
+                        Some(rollback) => format!("{}: {}", command.name(), rollback),
+                        None => format!("{}: откат не поддерживается", command.name()),
+                    }
+                }
+            })
+            .collect()
+    }
+
     /// Выполняет цепочку команд
     pub async fn execute(&self) -> Result<ChainResult, CommandError> {
+        self.execute_with_state(&RunState::new()).await
+    }
+
+    /// Выполняет цепочку в указанном режиме
+    ///
+    /// Режим, заданный цепочке, не меняется и используется при следующих вызовах `execute`.
+    pub async fn execute_with_mode(
+        &self,
+        mode: ChainExecutionMode,
+    ) -> Result<ChainResult, CommandError> {
+        self.execute_with_state(&RunState::new().with_mode(mode))
+            .await
+    }
+
+    /// Выполняет цепочку последовательно с сохранением хода выполнения в файл
+    ///
+    /// После каждой успешной команды ее позиция, имя и захваченные переменные
+    /// записываются в JSON-файл `state_path`. При повторном вызове команды,
+    /// записанные в файле, пропускаются, а их переменные восстанавливаются, поэтому
+    /// прерванный запуск продолжается с первой невыполненной команды. После
+    /// успешного завершения цепочки файл удаляется. Пропущенные команды не откатываются.
+    pub async fn execute_resumable(&self, state_path: &str) -> Result<ChainResult, CommandError> {
+        let state = RunState::new()
+            .with_mode(ChainExecutionMode::Sequential)
+            .with_checkpoint(Checkpoint::load(state_path)?);
+
+        let result = self.execute_with_state(&state).await?;
+
+        if let (true, Some(checkpoint)) = (result.success, &state.checkpoint) {
+            checkpoint.remove()?;
+        }
+
+        Ok(result)
+    }
+
+    /// Выполняет цепочку синхронно, без внешней среды выполнения tokio
+    ///
+    /// Создает собственную однопоточную среду выполнения. При вызове изнутри
+    /// среды выполнения tokio возвращает ошибку (используйте `execute`).
+    pub fn execute_blocking(&self) -> Result<ChainResult, CommandError> {
+        runtime::block_on(self.execute())
+    }
+
+    /// Выполняет цепочку с возможностью отмены из другой задачи
+    ///
+    /// При отмене новые команды не запускаются, процессы выполняющихся команд
+    /// завершаются, выполненные команды откатываются (если включен
+    /// `rollback_on_error`), и возвращается `CommandError::Interrupted`.
+    /// Отмена во время отката не прерывает его.
+    pub async fn execute_cancellable(
+        &self,
+        token: CancellationToken,
+    ) -> Result<ChainResult, CommandError> {
+        self.execute_with_state(&RunState::new().with_cancel(token))
+            .await
+    }
+
+    /// Выполняет цепочку как часть другой цепочки
+    ///
+    /// Возвращает также выполненные команды, чтобы их можно было откатить позже
+    /// через `rollback_executed`.
+    pub(crate) async fn execute_nested(
+        &self,
+        context: &ExecutionContext<'_>,
+    ) -> (Result<ChainResult, CommandError>, ExecutedCommands) {
+        // Окружение внешней цепочки дополняется и переопределяется окружением вложенной
+        let mut env = context.env.cloned().unwrap_or_default();
+        env.extend(self.env_vars.clone());
+
+        let state = RunState::new().with_dry_run(context.dry_run).with_env(env);
+        let result = self.execute_with_state(&state).await;
+
+        (result, state.take_executed())
+    }
+
+    /// Откатывает команды, выполненные ранее через `execute_nested`
+    ///
+    /// Возвращает результаты команд отката.
+    pub(crate) async fn rollback_executed(&self, executed: ExecutedCommands) -> Vec<CommandResult> {
+        let state = RunState::new().with_executed(executed);
+        self.rollback_commands(&state).await;
+
+        state.take_rollback_results()
+    }
+
+    /// Выполняет цепочку команд в отдельной задаче, отправляя события выполнения в поток
+    ///
+    /// События отправляются по мере выполнения команд. Поток завершается после
+    /// события `ChainFinished`, а итоговый результат доступен через `JoinHandle`.
+    pub fn execute_with_events(
+        self: Arc<Self>,
+    ) -> (
+        UnboundedReceiver<ChainEvent>,
+        JoinHandle<Result<ChainResult, CommandError>>,
+    ) {
+        let (sender, receiver) = mpsc::unbounded();
+        let state = RunState::new().with_events(sender);
+
+        let handle = tokio::spawn(async move { self.execute_with_state(&state).await });
+
+        (receiver, handle)
+    }
+
+    /// Выполняет цепочку команд с указанным состоянием запуска
+    async fn execute_with_state(&self, state: &RunState) -> Result<ChainResult, CommandError> {
+        // Режим, заданный при запуске, имеет приоритет над режимом цепочки
+        let mode = state.mode.unwrap_or(self.mode);
+
         // Выбираем режим выполнения
-        let execution_mode = match self.mode {
+        let execution_mode = match mode {
             ChainExecutionMode::Sequential => ExecutionMode::Sequential,
-            ChainExecutionMode::Parallel => ExecutionMode::Parallel,
+            // Независимые команды графа выполняются параллельно
+            ChainExecutionMode::Parallel | ChainExecutionMode::Dag => ExecutionMode::Parallel,
             ChainExecutionMode::Auto => {
                 // Если хотя бы одна команда последовательная, то выполняем последовательно
                 if self
@@ -142,119 +887,322 @@ impl CommandChain {
         };
 
         // Логируем начало выполнения
-        if let Some(logger) = &self.logger {
-            logger.info(&format!(
-                "Начало выполнения цепочки '{}' в режиме {:?}",
-                self.name, execution_mode
-            ));
+        if mode == ChainExecutionMode::Dag {
+            self.log_chain(
+                LogLevel::Info,
+                &format!(
+                    "Начало выполнения цепочки '{}' по графу зависимостей",
+                    self.name
+                ),
+            );
+        } else {
+            self.log_chain(
+                LogLevel::Info,
+                &format!(
+                    "Начало выполнения цепочки '{}' в режиме {:?}",
+                    self.name, execution_mode
+                ),
+            );
         }
 
-        let result = match execution_mode {
-            ExecutionMode::Sequential => self.execute_sequential().await,
-            ExecutionMode::Parallel => self.execute_parallel().await,
+        let execution = async {
+            match (mode, execution_mode) {
+                (ChainExecutionMode::Dag, _) => self.execute_dag(state).await,
+                (_, ExecutionMode::Sequential) => self.execute_sequential(state).await,
+                (_, ExecutionMode::Parallel) => self.execute_parallel(state).await,
+            }
         };
 
+        // Общий таймаут цепочки (никогда не истекает, если не установлен)
+        let deadline = async {
+            match self.chain_timeout {
+                Some(timeout) => {
+                    tokio::time::sleep(timeout).await;
+                    timeout
+                }
+                None => std::future::pending().await,
+            }
+        };
+
+        // Выполнение прерывается по таймауту или отмене; при этом выполняемые
+        // команды освобождаются, а их процессы завершаются
+        let outcome = tokio::select! {
+            result = execution => Ok(result),
+            timeout = deadline => Err(Interruption::Timeout(timeout)),
+            _ = state.cancelled() => Err(Interruption::Cancelled),
+        };
+
+        let result = match outcome {
+            Ok(result) => result,
+            Err(interruption) => {
+                let (message, error) = match interruption {
+                    Interruption::Timeout(timeout) => (
+                        format!(
+                            "Цепочка '{}' превысила общий таймаут {:?}",
+                            self.name, timeout
+                        ),
+                        CommandError::TimeoutError(format!(
+                            "цепочка '{}' не завершилась за {:?}",
+                            self.name, timeout
+                        )),
+                    ),
+                    Interruption::Cancelled => (
+                        format!("Выполнение цепочки '{}' отменено", self.name),
+                        CommandError::Interrupted(format!("цепочка '{}' отменена", self.name)),
+                    ),
+                };
+
+                self.log_chain(LogLevel::Error, &message);
+
+                Err(error)
+            }
+        };
+
+        // Откат выполняется вне прерываемой части ровно один раз, чтобы таймаут
+        // или отмена не прервали его на середине
+        let failed = !matches!(&result, Ok(chain_result) if chain_result.success);
+        if failed && self.rollback_on_error {
+            self.rollback_commands(state).await;
+        }
+
+        // Добавляем идентификаторы запущенных процессов и сведения об откате
+        let result = result.map(|mut chain_result| {
+            chain_result.pids = state.take_pids();
+            chain_result.rolled_back = state.rolled_back.load(Ordering::SeqCst);
+            chain_result.rollback_results = state.take_rollback_results();
+            chain_result
+        });
+
         // Логируем результат выполнения
-        if let Some(logger) = &self.logger {
-            match &result {
-                Ok(chain_result) => {
-                    if chain_result.success {
-                        logger.info(&format!(
+        match &result {
+            Ok(chain_result) => {
+                if chain_result.success {
+                    self.log_chain(
+                        LogLevel::Info,
+                        &format!(
                             "Цепочка '{}' успешно выполнена ({} команд)",
                             self.name,
                             chain_result.results.len()
-                        ));
-                    } else {
-                        logger.error(&format!(
+                        ),
+                    );
+                } else {
+                    self.log_chain(
+                        LogLevel::Error,
+                        &format!(
                             "Ошибка выполнения цепочки '{}': {}",
                             self.name,
                             chain_result
                                 .error
                                 .as_ref()
                                 .unwrap_or(&"<неизвестная ошибка>".to_string())
-                        ));
-                    }
+                        ),
+                    );
                 }
-                Err(err) => {
-                    logger.error(&format!(
+            }
+            Err(err) => {
+                self.log_chain(
+                    LogLevel::Error,
+                    &format!(
                         "Критическая ошибка выполнения цепочки '{}': {}",
                         self.name, err
-                    ));
-                }
+                    ),
+                );
             }
         }
 
+        if self.log_summary {
+            self.log_run_summary(&result, state);
+        }
+
+        state.emit(ChainEvent::ChainFinished {
+            success: matches!(&result, Ok(chain_result) if chain_result.success),
+        });
+
         result
     }
 
+    /// Записывает в лог итоговую строку запуска с контекстом для поиска и разбора
+    fn log_run_summary(&self, result: &Result<ChainResult, CommandError>, state: &RunState) {
+        let logger = match &self.logger {
+            Some(logger) => logger,
+            None => return,
+        };
+
+        let duration_ms = state.started.elapsed().as_millis() as u64;
+        let (success, summary, rolled_back) = match result {
+            Ok(chain_result) => (
+                chain_result.success,
+                Some(chain_result.summary()),
+                chain_result.rolled_back,
+            ),
+            // При прерывании результаты команд недоступны
+            Err(_) => (false, None, state.rolled_back.load(Ordering::SeqCst)),
+        };
+        let (succeeded, failed, skipped) = summary
+            .as_ref()
+            .map_or((0, 0, 0), |s| (s.succeeded, s.failed, s.skipped));
+
+        let context = LogContext::new().with_extra(json!({
+            "chain": self.name,
+            "success": success,
+            "commands": self.commands.len(),
+            "succeeded": succeeded,
+            "failed": failed,
+            "skipped": skipped,
+            "duration_ms": duration_ms,
+            "rolled_back": rolled_back,
+        }));
+
+        let message = format!(
+            "Итог цепочки '{}': {}, команд: {}, успешно: {}, с ошибкой: {}, пропущено: {}, {} мс, откат: {}",
+            self.name,
+            if success { "успешно" } else { "ошибка" },
+            self.commands.len(),
+            succeeded,
+            failed,
+            skipped,
+            duration_ms,
+            if rolled_back { "да" } else { "нет" }
+        );
+
+        let level = if success {
+            LogLevel::Info
+        } else {
+            LogLevel::Error
+        };
+        logger.log_with_context(level, &message, &context);
+    }
+
     /// Выполняет команды последовательно
-    async fn execute_sequential(&self) -> Result<ChainResult, CommandError> {
+    async fn execute_sequential(&self, state: &RunState) -> Result<ChainResult, CommandError> {
         let mut results = Vec::with_capacity(self.commands.len());
-        let mut executed_commands = Vec::new();
+        // Переменные, захваченные из вывода уже выполненных команд
+        // (при возобновлении - включая команды предыдущих запусков)
+        let mut variables = state
+            .checkpoint
+            .as_ref()
+            .map(Checkpoint::variables)
+            .unwrap_or_default();
+        let context = self.execution_context(state);
+        // Команды, выполненные в предыдущих запусках (при возобновлении)
+        let resumed = state
+            .checkpoint
+            .as_ref()
+            .map(Checkpoint::completed)
+            .unwrap_or_default();
+
+        for (index, command) in self.commands.iter().enumerate() {
+            let completed = CompletedCommand {
+                index,
+                name: command.name().to_string(),
+            };
+            if resumed.contains(&completed) {
+                self.log_command(
+                    LogLevel::Info,
+                    command.name(),
+                    &format!(
+                        "Команда '{}' выполнена в предыдущем запуске, пропускаем",
+                        command.name()
+                    ),
+                );
+
+                results.push(
+                    CommandResult::new(command.name())
+                        .skip("Команда выполнена в предыдущем запуске"),
+                );
+                continue;
+            }
 
-        for command in &self.commands {
             // Логируем выполнение команды
-            if let Some(logger) = &self.logger {
-                logger.info(&format!(
+            self.log_command(
+                LogLevel::Info,
+                command.name(),
+                &format!(
                     "Выполнение команды '{}' в цепочке '{}'",
                     command.name(),
                     self.name
-                ));
-            }
+                ),
+            );
 
-            match command.execute().await {
+            // Передаем команде результаты и захваченные переменные ранее выполненных команд
+            let command_context = context.with_results(&results).with_variables(&variables);
+
+            match self
+                .run_command(command, &command_context, state, Instant::now())
+                .await
+            {
                 Ok(result) => {
                     // Сохраняем команду как выполненную
-                    executed_commands.push(Arc::clone(command));
+                    state.record_executed(command, &result);
+
+                    if result.is_skipped() {
+                        // Логируем пропуск команды
+                        self.log_command(
+                            LogLevel::Info,
+                            command.name(),
+                            &format!("Команда '{}' пропущена", command.name()),
+                        );
 
-                    if result.success {
+                        results.push(result);
+                    } else if result.success {
                         // Логируем успешное выполнение
-                        if let Some(logger) = &self.logger {
-                            logger.info(&format!("Команда '{}' успешно выполнена", command.name()));
+                        self.log_command(
+                            LogLevel::Info,
+                            command.name(),
+                            &format!("Команда '{}' успешно выполнена", command.name()),
+                        );
+
+                        if let Some(checkpoint) = &state.checkpoint {
+                            checkpoint.record(index, command.name(), &result.captured)?;
                         }
 
+                        variables.extend(result.captured.clone());
                         results.push(result);
                     } else {
                         // Команда выполнилась с ошибкой
-                        if let Some(logger) = &self.logger {
-                            logger.error(&format!(
+                        self.log_command(
+                            LogLevel::Error,
+                            command.name(),
+                            &format!(
                                 "Ошибка выполнения команды '{}': {}",
                                 command.name(),
                                 result
                                     .error
                                     .as_ref()
                                     .unwrap_or(&String::from("<неизвестная ошибка>"))
-                            ));
-                        }
+                            ),
+                        );
 
                         results.push(result.clone());
 
-                        // Выполняем откат, если нужно
-                        if self.rollback_on_error {
-                            self.rollback_commands(&executed_commands).await;
-                        }
+                        let errors = vec![result
+                            .error
+                            .clone()
+                            .unwrap_or_else(|| "<неизвестная ошибка>".to_string())];
 
                         return Ok(ChainResult {
                             results,
                             success: false,
                             error: result.error,
+                            errors,
+                            pids: Vec::new(),
+                            rolled_back: false,
+                            rollback_results: Vec::new(),
+                            chosen_mode: ExecutionMode::Sequential,
                         });
                     }
                 }
                 Err(err) => {
                     // Логируем ошибку
-                    if let Some(logger) = &self.logger {
-                        logger.error(&format!(
+                    self.log_command(
+                        LogLevel::Error,
+                        command.name(),
+                        &format!(
                             "Критическая ошибка выполнения команды '{}': {}",
                             command.name(),
                             err
-                        ));
-                    }
-
-                    // Выполняем откат, если нужно
-                    if self.rollback_on_error {
-                        self.rollback_commands(&executed_commands).await;
-                    }
+                        ),
+                    );
 
                     return Err(err);
                 }
@@ -265,96 +1213,105 @@ impl CommandChain {
             results,
             success: true,
             error: None,
+            errors: Vec::new(),
+            pids: Vec::new(),
+            rolled_back: false,
+            rollback_results: Vec::new(),
+            chosen_mode: ExecutionMode::Sequential,
         })
     }
 
     /// Выполняет команды параллельно
-    async fn execute_parallel(&self) -> Result<ChainResult, CommandError> {
+    async fn execute_parallel(&self, state: &RunState) -> Result<ChainResult, CommandError> {
         if self.commands.is_empty() {
             return Ok(ChainResult {
                 results: Vec::new(),
                 success: true,
                 error: None,
+                errors: Vec::new(),
+                pids: Vec::new(),
+                rolled_back: false,
+                rollback_results: Vec::new(),
+                chosen_mode: ExecutionMode::Parallel,
             });
         }
 
         // Логируем параллельное выполнение
-        if let Some(logger) = &self.logger {
-            logger.info(&format!(
+        self.log_chain(
+            LogLevel::Info,
+            &format!(
                 "Параллельное выполнение {} команд в цепочке '{}'",
                 self.commands.len(),
                 self.name
-            ));
-        }
+            ),
+        );
 
-        // Выполняем команды параллельно
-        let futures = self
+        // Выполняем команды параллельно; каждый результат помечается индексом команды
+        let context = self.execution_context(state);
+        let mut running = self
             .commands
             .iter()
-            .map(|cmd| async move {
-                // Логируем выполнение команды
-                if let Some(logger) = &self.logger {
-                    logger.info(&format!(
-                        "Выполнение команды '{}' в цепочке '{}'",
+            .enumerate()
+            .map(|(index, cmd)| {
+                let context = &context;
+                let ready = Instant::now();
+                async move {
+                    // Логируем выполнение команды
+                    self.log_command(
+                        LogLevel::Info,
                         cmd.name(),
-                        self.name
-                    ));
-                }
+                        &format!(
+                            "Выполнение команды '{}' в цепочке '{}'",
+                            cmd.name(),
+                            self.name
+                        ),
+                    );
 
-                let result = cmd.execute().await;
+                    let result = self.run_command(cmd, context, state, ready).await;
 
-                if let Ok(ref cmd_result) = result {
-                    if cmd_result.success {
-                        // Логируем успешное выполнение
-                        if let Some(logger) = &self.logger {
-                            logger.info(&format!("Команда '{}' успешно выполнена", cmd.name()));
-                        }
-                    } else {
-                        // Логируем ошибку
-                        if let Some(logger) = &self.logger {
-                            logger.error(&format!(
-                                "Ошибка выполнения команды '{}': {}",
-                                cmd.name(),
-                                cmd_result
-                                    .error
-                                    .as_ref()
-                                    .unwrap_or(&String::from("<неизвестная ошибка>"))
-                            ));
-                        }
+                    // Команды сохраняются как выполненные по мере завершения,
+                    // чтобы их можно было откатить и при прерывании цепочки по таймауту
+                    if let Ok(ref cmd_result) = result {
+                        state.record_executed(cmd, cmd_result);
                     }
-                } else if let Err(ref err) = result {
-                    // Логируем критическую ошибку
-                    if let Some(logger) = &self.logger {
-                        logger.error(&format!(
-                            "Критическая ошибка выполнения команды '{}': {}",
-                            cmd.name(),
-                            err
-                        ));
-                    }
-                }
 
-                (cmd.clone(), result)
+                    self.log_outcome(cmd, &result);
+
+                    (index, result)
+                }
             })
-            .collect::<Vec<_>>();
+            .collect::<FuturesUnordered<_>>();
 
-        // Ждем завершения всех команд
-        let command_results = future::join_all(futures).await;
+        // Собираем результаты по мере завершения и восстанавливаем порядок добавления команд
+        let mut command_results = Vec::with_capacity(self.commands.len());
+        while let Some(indexed) = running.next().await {
+            command_results.push(indexed);
+        }
+        command_results.sort_by_key(|(index, _)| *index);
 
         // Обрабатываем результаты
         let mut results = Vec::new();
         let mut has_errors = false;
         let mut first_error = None;
-        let mut executed_commands = Vec::new();
+        let mut errors = Vec::new();
 
-        for (command, result) in command_results {
+        for (_, result) in command_results {
             match result {
                 Ok(cmd_result) => {
-                    executed_commands.push(command);
                     results.push(cmd_result.clone());
 
-                    if !cmd_result.success && !has_errors {
-                        has_errors = true;
-                        first_error = cmd_result.error.clone();
+                    if !cmd_result.success {
+                        if !has_errors {
+                            has_errors = true;
+                            first_error = cmd_result.error.clone();
+                        }
+
+                        errors.push(
+                            cmd_result
+                                .error
+                                .clone()
+                                .unwrap_or_else(|| "<неизвестная ошибка>".to_string()),
+                        );
                     }
                 }
                 Err(err) => {
@@ -362,36 +1319,264 @@ impl CommandChain {
                         has_errors = true;
                         first_error = Some(err.to_string());
                     }
+
+                    errors.push(err.to_string());
                 }
             }
         }
 
-        // Выполняем откат, если есть ошибки и установлен флаг отката
-        if has_errors && self.rollback_on_error {
-            self.rollback_commands(&executed_commands).await;
+        Ok(ChainResult {
+            results,
+            success: !has_errors,
+            error: first_error,
+            errors,
+            pids: Vec::new(),
+            rolled_back: false,
+            rollback_results: Vec::new(),
+            chosen_mode: ExecutionMode::Parallel,
+        })
+    }
+
+    /// Логирует результат выполнения команды
+    fn log_outcome(
+        &self,
+        command: &Arc<dyn Command>,
+        result: &Result<CommandResult, CommandError>,
+    ) {
+        let (level, message) = match result {
+            // Логируем успешное выполнение
+            Ok(cmd_result) if cmd_result.success => (
+                LogLevel::Info,
+                format!("Команда '{}' успешно выполнена", command.name()),
+            ),
+            // Логируем ошибку
+            Ok(cmd_result) => (
+                LogLevel::Error,
+                format!(
+                    "Ошибка выполнения команды '{}': {}",
+                    command.name(),
+                    cmd_result
+                        .error
+                        .as_ref()
+                        .unwrap_or(&String::from("<неизвестная ошибка>"))
+                ),
+            ),
+            // Логируем критическую ошибку
+            Err(err) => (
+                LogLevel::Error,
+                format!(
+                    "Критическая ошибка выполнения команды '{}': {}",
+                    command.name(),
+                    err
+                ),
+            ),
+        };
+
+        self.log_command(level, command.name(), &message);
+    }
+
+    /// Выполняет команды по графу зависимостей
+    ///
+    /// Команда запускается, как только все ее зависимости успешно выполнены.
+    /// Команды, зависимости которых завершились с ошибкой, не выполняются.
+    async fn execute_dag(&self, state: &RunState) -> Result<ChainResult, CommandError> {
+        let DependencyGraph {
+            dependents,
+            mut in_degree,
+        } = DependencyGraph::build(&self.commands).map_err(|err| {
+            CommandError::ExecutionError(format!("Цепочка '{}': {}", self.name, err))
+        })?;
+
+        // Зависимости каждой команды в порядке добавления в цепочку
+        let mut dependencies = vec![Vec::new(); self.commands.len()];
+        for (index, command_dependents) in dependents.iter().enumerate() {
+            for &dependent in command_dependents {
+                dependencies[dependent].push(index);
+            }
+        }
+
+        let context = self.execution_context(state);
+        // Команде передаются результаты ее выполненных зависимостей
+        let run = |index: usize, dependency_results: Vec<CommandResult>| {
+            let command = &self.commands[index];
+            let context = &context;
+            let ready = Instant::now();
+
+            async move {
+                let context = context.with_results(&dependency_results);
+
+                // Логируем выполнение команды
+                self.log_command(
+                    LogLevel::Info,
+                    command.name(),
+                    &format!(
+                        "Выполнение команды '{}' в цепочке '{}'",
+                        command.name(),
+                        self.name
+                    ),
+                );
+
+                let result = self.run_command(command, &context, state, ready).await;
+
+                if let Ok(ref cmd_result) = result {
+                    state.record_executed(command, cmd_result);
+                }
+
+                self.log_outcome(command, &result);
+
+                (index, result)
+            }
+        };
+
+        // Запускаем команды без зависимостей
+        let mut running: FuturesUnordered<_> = (0..self.commands.len())
+            .filter(|index| in_degree[*index] == 0)
+            .map(|index| run(index, Vec::new()))
+            .collect();
+
+        let mut results = Vec::new();
+        let mut completed: Vec<Option<CommandResult>> = vec![None; self.commands.len()];
+        let mut errors = Vec::new();
+        let mut blocked = vec![false; self.commands.len()];
+
+        while let Some((index, result)) = running.next().await {
+            let succeeded = match result {
+                Ok(cmd_result) => {
+                    let success = cmd_result.success;
+                    if !success {
+                        errors.push(
+                            cmd_result
+                                .error
+                                .clone()
+                                .unwrap_or_else(|| "<неизвестная ошибка>".to_string()),
+                        );
+                    }
+                    completed[index] = Some(cmd_result.clone());
+                    results.push(cmd_result);
+                    success
+                }
+                Err(err) => {
+                    errors.push(err.to_string());
+                    false
+                }
+            };
+
+            // Команды, зависимости которых не выполнены, считаются завершенными с ошибкой,
+            // чтобы не запускать и зависящие от них команды
+            let mut finished = vec![(index, succeeded)];
+
+            while let Some((index, succeeded)) = finished.pop() {
+                for &dependent in &dependents[index] {
+                    if !succeeded {
+                        blocked[dependent] = true;
+                    }
+
+                    in_degree[dependent] -= 1;
+                    if in_degree[dependent] > 0 {
+                        continue;
+                    }
+
+                    if blocked[dependent] {
+                        let message = format!(
+                            "Команда '{}' не выполнена: зависимость завершилась с ошибкой",
+                            self.commands[dependent].name()
+                        );
+
+                        self.log_command(
+                            LogLevel::Warning,
+                            self.commands[dependent].name(),
+                            &message,
+                        );
+
+                        errors.push(message);
+                        finished.push((dependent, false));
+                    } else {
+                        let dependency_results = dependencies[dependent]
+                            .iter()
+                            .filter_map(|&dependency| completed[dependency].clone())
+                            .collect();
+                        running.push(run(dependent, dependency_results));
+                    }
+                }
+            }
         }
 
+        let has_errors = !errors.is_empty();
+
         Ok(ChainResult {
             results,
             success: !has_errors,
-            error: first_error,
+            error: errors.first().cloned(),
+            errors,
+            pids: Vec::new(),
+            rolled_back: false,
+            rollback_results: Vec::new(),
+            chosen_mode: ExecutionMode::Parallel,
         })
     }
 
-    /// Выполняет откат команд
-    async fn rollback_commands(&self, commands: &[Arc<dyn Command>]) {
+    /// Выполняет откат команд, выполненных в текущем запуске
+    ///
+    /// Команды откатываются вместе с результатами их выполнения, чтобы команды отката
+    /// могли использовать переменные, захваченные из вывода.
+    async fn rollback_commands(&self, state: &RunState) {
+        state.emit(ChainEvent::RollbackStarted);
+        state.rolled_back.store(true, Ordering::SeqCst);
+        let commands = state.take_executed();
+
+        let variables: HashMap<String, String> = commands
+            .iter()
+            .flat_map(|(_, result)| result.captured.clone())
+            .collect();
+        let mut context = ExecutionContext::new()
+            .with_variables(&variables)
+            .with_pids(&state.pids)
+            .with_env(self.env(state))
+            .with_chain_name(&self.name);
+
         if let Some(logger) = &self.logger {
-            logger.warning(&format!("Выполнение отката для цепочки '{}'", self.name));
+            context = context.with_logger(logger.as_ref());
+        }
+
+        if self.uses_variable_cache() {
+            context = context.with_variable_cache(&state.variables);
+        }
+
+        if !self.overrides.is_empty() {
+            context = context.with_overrides(&self.overrides);
         }
 
+        if let Some(timeout) = self.default_timeout {
+            context = context.with_default_timeout(timeout);
+        }
+
+        self.log_chain(
+            LogLevel::Warning,
+            &format!("Выполнение отката для цепочки '{}'", self.name),
+        );
+
         // Откатываем команды в обратном порядке
-        for command in commands.iter().rev() {
-            if command.supports_rollback() {
-                if let Some(logger) = &self.logger {
-                    logger.info(&format!("Откат команды '{}'", command.name()));
-                }
+        for (command, result) in commands.iter().rev() {
+            if command.skip_rollback() {
+                self.log_command(
+                    LogLevel::Warning,
+                    command.name(),
+                    &format!(
+                        "Откат команды '{}' пропущен: команда исключена из отката",
+                        command.name()
+                    ),
+                );
+            } else if command.supports_rollback() {
+                self.log_command(
+                    LogLevel::Info,
+                    command.name(),
+                    &format!("Откат команды '{}'", command.name()),
+                );
+
+                // Команде отката доступен вывод прямой команды через {__output__}
+                let context = context.with_forward_result(result);
 
-                match command.rollback().await {
+                match command.rollback_with_context(&context).await {
                     Ok(result) => {
                         if result.success {
                             if let Some(logger) = &self.logger {
@@ -399,34 +1584,42 @@ impl CommandChain {
                                     .info(&format!("Успешный откат команды '{}'", command.name()));
                             }
                         } else {
-                            if let Some(logger) = &self.logger {
-                                logger.error(&format!(
+                            self.log_command(
+                                LogLevel::Error,
+                                command.name(),
+                                &format!(
                                     "Ошибка отката команды '{}': {}",
                                     command.name(),
-                                    result
-                                        .error
-                                        .unwrap_or_else(|| "<неизвестная ошибка>".to_string())
-                                ));
-                            }
+                                    result.error.as_deref().unwrap_or("<неизвестная ошибка>")
+                                ),
+                            );
                         }
+
+                        state.record_rollback(result);
                     }
                     Err(err) => {
-                        if let Some(logger) = &self.logger {
-                            logger.error(&format!(
+                        self.log_command(
+                            LogLevel::Error,
+                            command.name(),
+                            &format!(
                                 "Критическая ошибка отката команды '{}': {}",
                                 command.name(),
                                 err
-                            ));
-                        }
+                            ),
+                        );
+
+                        state.record_rollback(
+                            CommandResult::new(&format!("{}_rollback", command.name()))
+                                .failure(err.to_string(), None),
+                        );
                     }
                 }
             } else {
-                if let Some(logger) = &self.logger {
-                    logger.warning(&format!(
-                        "Команда '{}' не поддерживает откат",
-                        command.name()
-                    ));
-                }
+                self.log_command(
+                    LogLevel::Warning,
+                    command.name(),
+                    &format!("Команда '{}' не поддерживает откат", command.name()),
+                );
             }
         }
     }
@@ -0,0 +1,158 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::builder::CommandBuilder;
+use crate::chain::command_chain::{ChainExecutionMode, CommandChain};
+use crate::command::traits::CommandError;
+use crate::command::{ExecutionMode, ShellCommand};
+
+/// Описание команды в конфигурационном файле цепочки
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CommandConfig {
+    /// Название команды
+    pub name: String,
+
+    /// Командная строка для выполнения
+    pub command: String,
+
+    /// Таймаут выполнения команды в секундах
+    #[serde(default)]
+    pub timeout: Option<u64>,
+
+    /// Команда для отката
+    #[serde(default)]
+    pub rollback: Option<String>,
+
+    /// Рабочая директория для выполнения команды
+    #[serde(default)]
+    pub working_dir: Option<String>,
+
+    /// Переменные окружения
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+
+    /// Режим выполнения команды
+    #[serde(default)]
+    pub mode: Option<ExecutionMode>,
+
+    /// Имена команд, от которых зависит команда (режим `Dag`)
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+}
+
+impl CommandConfig {
+    /// Создает команду по описанию
+    pub fn build(&self) -> ShellCommand {
+        let mut builder = CommandBuilder::new(&self.name, &self.command);
+
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+
+        if let Some(rollback) = &self.rollback {
+            builder = builder.rollback(rollback);
+        }
+
+        if let Some(dir) = &self.working_dir {
+            builder = builder.working_dir(dir);
+        }
+
+        for (key, value) in &self.env {
+            builder = builder.env_var(key, value);
+        }
+
+        if let Some(mode) = self.mode {
+            builder = builder.execution_mode(mode);
+        }
+
+        if !self.depends_on.is_empty() {
+            let names: Vec<&str> = self.depends_on.iter().map(String::as_str).collect();
+            builder = builder.depends_on(&names);
+        }
+
+        builder.build()
+    }
+}
+
+/// Описание цепочки команд в конфигурационном файле (YAML или JSON)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChainConfig {
+    /// Название цепочки
+    pub name: String,
+
+    /// Режим выполнения цепочки
+    #[serde(default = "default_mode")]
+    pub mode: ChainExecutionMode,
+
+    /// Откатывать ли выполненные команды в случае ошибки
+    #[serde(default = "default_rollback_on_error")]
+    pub rollback_on_error: bool,
+
+    /// Команды цепочки
+    #[serde(default)]
+    pub commands: Vec<CommandConfig>,
+}
+
+/// Режим выполнения цепочки по умолчанию
+fn default_mode() -> ChainExecutionMode {
+    ChainExecutionMode::Sequential
+}
+
+/// Откат при ошибке по умолчанию включен, как и в `CommandChain::new`
+fn default_rollback_on_error() -> bool {
+    true
+}
+
+impl ChainConfig {
+    /// Загружает описание цепочки из файла
+    ///
+    /// Файлы с расширением `.yaml` или `.yml` разбираются как YAML, остальные как JSON.
+    pub fn from_file(path: &str) -> Result<Self, CommandError> {
+        let contents = std::fs::read_to_string(path).map_err(|e| {
+            CommandError::ExecutionError(format!(
+                "Не удалось прочитать файл конфигурации цепочки {}: {}",
+                path, e
+            ))
+        })?;
+
+        let is_yaml = matches!(
+            Path::new(path).extension().and_then(|ext| ext.to_str()),
+            Some("yaml") | Some("yml")
+        );
+
+        if is_yaml {
+            Self::from_yaml(&contents)
+        } else {
+            Self::from_json(&contents)
+        }
+    }
+
+    /// Разбирает описание цепочки из YAML
+    pub fn from_yaml(contents: &str) -> Result<Self, CommandError> {
+        serde_yaml::from_str(contents).map_err(|e| {
+            CommandError::ExecutionError(format!("Не удалось разобрать YAML цепочки: {}", e))
+        })
+    }
+
+    /// Разбирает описание цепочки из JSON
+    pub fn from_json(contents: &str) -> Result<Self, CommandError> {
+        serde_json::from_str(contents).map_err(|e| {
+            CommandError::ExecutionError(format!("Не удалось разобрать JSON цепочки: {}", e))
+        })
+    }
+
+    /// Создает цепочку команд по описанию
+    pub fn build(&self) -> CommandChain {
+        let mut chain = CommandChain::new(&self.name);
+        chain
+            .with_execution_mode(self.mode)
+            .with_rollback_on_error(self.rollback_on_error);
+
+        for command in &self.commands {
+            chain.add_command(command.build());
+        }
+
+        chain
+    }
+}
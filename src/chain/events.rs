@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+
+/// Событие жизненного цикла выполнения цепочки команд
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChainEvent {
+    /// Команда запущена
+    CommandStarted {
+        /// Имя команды
+        name: String,
+    },
+
+    /// Команда завершена
+    CommandFinished {
+        /// Имя команды
+        name: String,
+        /// Успешность выполнения
+        success: bool,
+        /// Длительность выполнения в миллисекундах
+        duration_ms: u64,
+    },
+
+    /// Начат откат выполненных команд
+    RollbackStarted,
+
+    /// Выполнение цепочки завершено
+    ChainFinished {
+        /// Общий результат (успех/неудача)
+        success: bool,
+    },
+}
@@ -0,0 +1,86 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+
+use crate::builder::BuildError;
+use crate::command::Command;
+
+/// Граф зависимостей команд цепочки
+#[derive(Debug, Clone)]
+pub(crate) struct DependencyGraph {
+    /// Для каждой команды - индексы зависящих от нее команд
+    pub dependents: Vec<Vec<usize>>,
+
+    /// Для каждой команды - число ее зависимостей
+    pub in_degree: Vec<usize>,
+}
+
+impl DependencyGraph {
+    /// Строит граф по зависимостям команд
+    ///
+    /// Возвращает ошибку при ссылке на неизвестную команду, повторяющихся именах
+    /// или цикле в графе.
+    pub fn build(commands: &[Arc<dyn Command>]) -> Result<Self, BuildError> {
+        let mut indices = HashMap::new();
+        for (index, command) in commands.iter().enumerate() {
+            if indices.insert(command.name(), index).is_some() {
+                return Err(BuildError::DuplicateCommand(command.name().to_string()));
+            }
+        }
+
+        let mut dependents = vec![Vec::new(); commands.len()];
+        let mut in_degree = vec![0; commands.len()];
+
+        for (index, command) in commands.iter().enumerate() {
+            for dependency in command.dependencies() {
+                let dependency_index = *indices.get(dependency.as_str()).ok_or_else(|| {
+                    BuildError::UnknownDependency {
+                        command: command.name().to_string(),
+                        dependency: dependency.clone(),
+                    }
+                })?;
+
+                dependents[dependency_index].push(index);
+                in_degree[index] += 1;
+            }
+        }
+
+        let graph = Self {
+            dependents,
+            in_degree,
+        };
+
+        let cycle = graph.find_cycle();
+        if !cycle.is_empty() {
+            return Err(BuildError::DependencyCycle(
+                cycle
+                    .into_iter()
+                    .map(|index| commands[index].name().to_string())
+                    .collect(),
+            ));
+        }
+
+        Ok(graph)
+    }
+
+    /// Возвращает индексы команд, которые невозможно упорядочить из-за цикла
+    fn find_cycle(&self) -> Vec<usize> {
+        // Топологическая сортировка: команды, оставшиеся с зависимостями, входят в цикл
+        let mut remaining = self.in_degree.clone();
+        let mut queue: VecDeque<usize> = (0..remaining.len())
+            .filter(|index| remaining[*index] == 0)
+            .collect();
+
+        while let Some(index) = queue.pop_front() {
+            for &dependent in &self.dependents[index] {
+                remaining[dependent] -= 1;
+                if remaining[dependent] == 0 {
+                    queue.push_back(dependent);
+                }
+            }
+        }
+
+        (0..remaining.len())
+            .filter(|index| remaining[*index] > 0)
+            .collect()
+    }
+}
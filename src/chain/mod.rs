@@ -1,3 +1,14 @@
+pub mod chain_command;
+pub(crate) mod checkpoint;
 pub mod command_chain;
+pub mod config;
+pub mod events;
+pub(crate) mod graph;
 
-pub use command_chain::{ChainExecutionMode, CommandChain};
+pub use chain_command::ChainCommand;
+pub use command_chain::{
+    AfterHook, BeforeHook, ChainExecutionMode, ChainResult, ChainSummary, CommandChain,
+    ProgressHook,
+};
+pub use config::{ChainConfig, CommandConfig};
+pub use events::ChainEvent;
@@ -1,12 +1,18 @@
 use async_trait::async_trait;
-use futures::future;
-use std::sync::Arc;
+use futures::stream::{self, StreamExt};
+use std::cmp::Reverse;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
+use crate::command::context::ExecutionContext;
 use crate::command::traits::{
-    Command, CommandError, CommandExecution, CommandResult, ExecutionMode,
+    Command, CommandError, CommandExecution, CommandResult, ExecutionMode, RollbackState,
 };
 use crate::visitor::Visitor;
 
+/// Подкоманды, выполненные при запуске, и их результаты (для отката)
+type ExecutedSubcommands = Vec<(Arc<dyn Command>, Option<CommandResult>)>;
+
 /// Структура для группировки и последовательного или параллельного выполнения команд
 #[derive(Clone)]
 pub struct CompositeCommand {
@@ -18,6 +24,15 @@ pub struct CompositeCommand {
 
     /// Режим выполнения
     mode: ExecutionMode,
+
+    /// Откатывать ли выполненные подкоманды в случае ошибки
+    rollback_on_error: bool,
+
+    /// Общий таймаут выполнения подкоманд
+    timeout: Option<Duration>,
+
+    /// Максимальное число одновременно выполняемых подкоманд в параллельном режиме
+    max_concurrency: Option<usize>,
 }
 
 impl CompositeCommand {
@@ -27,12 +42,20 @@ impl CompositeCommand {
             name: name.to_string(),
             commands: Vec::new(),
             mode: ExecutionMode::Sequential,
+            rollback_on_error: false,
+            timeout: None,
+            max_concurrency: None,
         }
     }
 
     /// Добавляет команду в группу
     pub fn add_command<C: Command + 'static>(&mut self, command: C) -> &mut Self {
-        self.commands.push(Arc::new(command));
+        self.push_command(Arc::new(command))
+    }
+
+    /// Добавляет уже обернутую в `Arc` команду в группу
+    pub(crate) fn push_command(&mut self, command: Arc<dyn Command>) -> &mut Self {
+        self.commands.push(command);
         self
     }
 
@@ -42,14 +65,52 @@ impl CompositeCommand {
         self
     }
 
+    /// Устанавливает флаг отката выполненных подкоманд при ошибке (по умолчанию выключен)
+    pub fn with_rollback_on_error(&mut self, rollback: bool) -> &mut Self {
+        self.rollback_on_error = rollback;
+        self
+    }
+
+    /// Устанавливает общий таймаут выполнения подкоманд
+    pub fn with_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Ограничивает число одновременно выполняемых подкоманд в параллельном режиме
+    ///
+    /// Значение 0 трактуется как 1. Без ограничения все подкоманды запускаются сразу.
+    pub fn with_max_concurrency(&mut self, limit: usize) -> &mut Self {
+        self.max_concurrency = Some(limit.max(1));
+        self
+    }
+
+    /// Сохраняет подкоманду как выполненную
+    fn record_executed(
+        executed: &Mutex<ExecutedSubcommands>,
+        command: &Arc<dyn Command>,
+        result: &CommandResult,
+    ) {
+        executed
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push((Arc::clone(command), Some(result.clone())));
+    }
+
     /// Выполняет команды последовательно
-    async fn execute_sequential(&self) -> Result<CommandResult, CommandError> {
+    async fn execute_sequential(
+        &self,
+        context: &ExecutionContext<'_>,
+        executed: &Mutex<ExecutedSubcommands>,
+    ) -> Result<CommandResult, CommandError> {
         let result = CommandResult::new(&self.name);
         let mut all_output = String::new();
 
         for command in &self.commands {
-            match command.execute().await {
+            match command.execute_with_context(context).await {
                 Ok(cmd_result) => {
+                    Self::record_executed(executed, command, &cmd_result);
+
                     if !cmd_result.success {
                         return Ok(result.failure(
                             format!(
@@ -82,23 +143,51 @@ impl CompositeCommand {
     }
 
     /// Выполняет команды параллельно
-    async fn execute_parallel(&self) -> Result<CommandResult, CommandError> {
+    async fn execute_parallel(
+        &self,
+        context: &ExecutionContext<'_>,
+        executed: &Mutex<ExecutedSubcommands>,
+    ) -> Result<CommandResult, CommandError> {
         let result = CommandResult::new(&self.name);
 
-        let futures = self
-            .commands
-            .iter()
-            .map(|cmd| cmd.execute())
+        let limit = self.max_concurrency.unwrap_or(self.commands.len()).max(1);
+
+        // Futures ленивы: подкоманда запускается, только когда поток берет ее в работу
+        // При ограничении числа одновременных команд первыми запускаются команды
+        // с большим приоритетом; сортировка устойчива и сохраняет порядок добавления
+        let mut order = (0..self.commands.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&index| Reverse(self.commands[index].priority()));
+
+        let futures = order
+            .into_iter()
+            .map(|index| (index, &self.commands[index]))
+            .map(|(index, cmd)| async move {
+                let res = cmd.execute_with_context(context).await;
+
+                // Подкоманды сохраняются по мере завершения, чтобы откатить их и при таймауте
+                if let Ok(cmd_result) = &res {
+                    Self::record_executed(executed, cmd, cmd_result);
+                }
+
+                (index, res)
+            })
             .collect::<Vec<_>>();
 
-        let results = future::join_all(futures).await;
+        let mut results = stream::iter(futures)
+            .buffer_unordered(limit)
+            .collect::<Vec<_>>()
+            .await;
+
+        // Результаты собираются в порядке подкоманд, а не в порядке завершения
+        results.sort_by_key(|(index, _)| *index);
+        let results = results.into_iter().map(|(_, res)| res);
 
         let mut all_output = String::new();
         let mut has_errors = false;
         let mut first_error = None;
         let mut first_exit_code = None;
 
-        for (i, res) in results.into_iter().enumerate() {
+        for (i, res) in results.enumerate() {
             match res {
                 Ok(cmd_result) => {
                     if !cmd_result.success && !has_errors {
@@ -143,15 +232,34 @@ impl CompositeCommand {
         }
     }
 
-    /// Выполняет откат команд в обратном порядке
-    async fn rollback_commands(&self) -> Result<CommandResult, CommandError> {
+    /// Выполняет откат указанных команд в обратном порядке
+    ///
+    /// Если известен результат прямой команды, он передается в ее откат. Логгер,
+    /// переменные, окружение и сбор идентификаторов процессов берутся из `context`.
+    async fn rollback_commands(
+        &self,
+        commands: &[(Arc<dyn Command>, Option<CommandResult>)],
+        context: &ExecutionContext<'_>,
+    ) -> Result<CommandResult, CommandError> {
+        let base_context = ExecutionContext {
+            results: &[],
+            forward_result: None,
+            ..*context
+        };
         let result = CommandResult::new(&format!("{}_rollback", self.name));
         let mut all_output = String::new();
 
         // Откатываем команды в обратном порядке
-        for command in self.commands.iter().rev() {
-            if command.supports_rollback() {
-                match command.rollback().await {
+        for (command, forward_result) in commands.iter().rev() {
+            if command.skip_rollback() {
+                all_output.push_str(&format!("Команда {} исключена из отката\n", command.name()));
+            } else if command.supports_rollback() {
+                let context = match forward_result {
+                    Some(forward_result) => base_context.with_forward_result(forward_result),
+                    None => base_context,
+                };
+
+                match command.rollback_with_context(&context).await {
                     Ok(cmd_result) => {
                         all_output.push_str(&format!(
                             "Откат {}:\n{}\n",
@@ -191,6 +299,8 @@ impl std::fmt::Debug for CompositeCommand {
             .field("name", &self.name)
             .field("commands_count", &self.commands.len())
             .field("mode", &self.mode)
+            .field("rollback_on_error", &self.rollback_on_error)
+            .field("timeout", &self.timeout)
             .finish()
     }
 }
@@ -198,14 +308,93 @@ impl std::fmt::Debug for CompositeCommand {
 #[async_trait]
 impl CommandExecution for CompositeCommand {
     async fn execute(&self) -> Result<CommandResult, CommandError> {
-        match self.mode {
-            ExecutionMode::Sequential => self.execute_sequential().await,
-            ExecutionMode::Parallel => self.execute_parallel().await,
+        self.execute_with_context(&ExecutionContext::default())
+            .await
+    }
+
+    async fn execute_with_context(
+        &self,
+        context: &ExecutionContext<'_>,
+    ) -> Result<CommandResult, CommandError> {
+        let executed = Mutex::new(Vec::new());
+
+        let run = async {
+            match self.mode {
+                ExecutionMode::Sequential => self.execute_sequential(context, &executed).await,
+                ExecutionMode::Parallel => self.execute_parallel(context, &executed).await,
+            }
+        };
+
+        // Применяем общий таймаут, если установлен
+        let outcome = match self.timeout {
+            Some(timeout) => match tokio::time::timeout(timeout, run).await {
+                Ok(outcome) => outcome,
+                Err(_) => Err(CommandError::TimeoutError(format!(
+                    "составная команда '{}' не завершилась за {:?}",
+                    self.name, timeout
+                ))),
+            },
+            None => run.await,
+        };
+
+        let failed = match &outcome {
+            Ok(result) => !result.success,
+            Err(_) => true,
+        };
+
+        // Откатываем только подкоманды, выполненные при этом запуске
+        if failed && self.rollback_on_error {
+            if let Some(logger) = context.logger {
+                logger.warning(&format!(
+                    "Выполнение отката составной команды '{}'",
+                    self.name
+                ));
+            }
+
+            let executed = executed.into_inner().unwrap_or_else(|e| e.into_inner());
+            self.rollback_commands(&executed, context).await?;
+
+            // Признак отката сохраняется в результате запуска, чтобы откат цепочкой не повторял его
+            return outcome.map(|result| {
+                result.with_rollback_state(RollbackState {
+                    rolled_back: true,
+                    ..RollbackState::default()
+                })
+            });
         }
+
+        outcome
     }
 
     async fn rollback(&self) -> Result<CommandResult, CommandError> {
-        self.rollback_commands().await
+        self.rollback_with_context(&ExecutionContext::new()).await
+    }
+
+    /// Откатывает все подкоманды
+    ///
+    /// Если подкоманды запуска, результат которого передан в `context.forward_result`,
+    /// уже откачены из-за ошибки (`with_rollback_on_error`), повторный откат не выполняется.
+    async fn rollback_with_context(
+        &self,
+        context: &ExecutionContext<'_>,
+    ) -> Result<CommandResult, CommandError> {
+        let rolled_back = context
+            .forward_result
+            .and_then(CommandResult::rollback_state)
+            .is_some_and(|state| state.rolled_back);
+
+        if rolled_back {
+            return Ok(CommandResult::new(&format!("{}_rollback", self.name))
+                .success("Откат уже выполнен составной командой".to_string()));
+        }
+
+        let commands: ExecutedSubcommands = self
+            .commands
+            .iter()
+            .map(|command| (Arc::clone(command), None))
+            .collect();
+
+        self.rollback_commands(&commands, context).await
     }
 
     fn name(&self) -> &str {
@@ -0,0 +1,143 @@
+use async_trait::async_trait;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::command::context::ExecutionContext;
+use crate::command::traits::{
+    Command, CommandError, CommandExecution, CommandResult, ExecutionMode,
+};
+use crate::visitor::Visitor;
+
+/// Условие выполнения команды, вычисляемое по результатам предыдущих команд
+pub type CommandPredicate = Box<dyn Fn(&[CommandResult]) -> bool + Send + Sync>;
+
+/// Команда, выполняемая только при выполнении условия
+///
+/// Условию передаются результаты из контекста выполнения (`ExecutionContext::results`):
+/// в цепочке это результаты предыдущих команд (при последовательном выполнении) или
+/// зависимостей команды (по графу зависимостей). При параллельном выполнении
+/// результатов нет, и условие получает пустой срез.
+pub struct ConditionalCommand {
+    /// Оборачиваемая команда
+    command: Arc<dyn Command>,
+
+    /// Условие выполнения
+    predicate: CommandPredicate,
+}
+
+impl ConditionalCommand {
+    /// Создает новую условную команду
+    pub fn new<C, P>(command: C, predicate: P) -> Self
+    where
+        C: Command + 'static,
+        P: Fn(&[CommandResult]) -> bool + Send + Sync + 'static,
+    {
+        Self {
+            command: Arc::new(command),
+            predicate: Box::new(predicate),
+        }
+    }
+
+    /// Создает команду, выполняемую, только если путь существует на момент выполнения
+    pub fn if_path_exists<C>(command: C, path: impl Into<PathBuf>) -> Self
+    where
+        C: Command + 'static,
+    {
+        let path = path.into();
+        Self::new(command, move |_| path.exists())
+    }
+
+    /// Создает команду, выполняемую, только если путь не существует на момент выполнения
+    pub fn if_path_missing<C>(command: C, path: impl Into<PathBuf>) -> Self
+    where
+        C: Command + 'static,
+    {
+        let path = path.into();
+        Self::new(command, move |_| !path.exists())
+    }
+
+    /// Проверяет условие по результатам предыдущих команд
+    pub fn should_run(&self, results: &[CommandResult]) -> bool {
+        (self.predicate)(results)
+    }
+
+    /// Возвращает оборачиваемую команду
+    pub fn inner(&self) -> &Arc<dyn Command> {
+        &self.command
+    }
+}
+
+impl std::fmt::Debug for ConditionalCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConditionalCommand")
+            .field("name", &self.command.name())
+            .field("mode", &self.command.execution_mode())
+            .finish()
+    }
+}
+
+#[async_trait]
+impl CommandExecution for ConditionalCommand {
+    async fn execute(&self) -> Result<CommandResult, CommandError> {
+        self.execute_with_context(&ExecutionContext::default())
+            .await
+    }
+
+    async fn execute_with_context(
+        &self,
+        context: &ExecutionContext<'_>,
+    ) -> Result<CommandResult, CommandError> {
+        if self.should_run(context.results) {
+            self.command.execute_with_context(context).await
+        } else {
+            Ok(CommandResult::new(self.name()).skip("Условие выполнения не выполнено"))
+        }
+    }
+
+    async fn rollback(&self) -> Result<CommandResult, CommandError> {
+        self.command.rollback().await
+    }
+
+    async fn rollback_with_context(
+        &self,
+        context: &ExecutionContext<'_>,
+    ) -> Result<CommandResult, CommandError> {
+        self.command.rollback_with_context(context).await
+    }
+
+    fn name(&self) -> &str {
+        self.command.name()
+    }
+
+    fn execution_mode(&self) -> ExecutionMode {
+        self.command.execution_mode()
+    }
+
+    fn supports_rollback(&self) -> bool {
+        self.command.supports_rollback()
+    }
+
+    fn skip_rollback(&self) -> bool {
+        self.command.skip_rollback()
+    }
+
+    fn dependencies(&self) -> Vec<String> {
+        self.command.dependencies()
+    }
+
+    fn priority(&self) -> i32 {
+        self.command.priority()
+    }
+
+    fn rollback_preview(&self) -> Option<String> {
+        self.command.rollback_preview()
+    }
+}
+
+#[async_trait]
+impl Command for ConditionalCommand {
+    fn accept(&self, visitor: &mut dyn Visitor) {
+        visitor.visit_conditional_command(self);
+        self.command.accept(visitor);
+    }
+}
@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::command::traits::CommandResult;
+use crate::logging::Logger;
+
+/// Значение переменной, определенное при подстановке в командную строку
+#[derive(Debug, Clone)]
+pub(crate) struct ResolvedVariable {
+    /// Значение переменной
+    pub value: String,
+
+    /// Значение скрывается в отображаемой командной строке
+    pub hidden: bool,
+}
+
+impl ResolvedVariable {
+    /// Создает отображаемое значение
+    pub(crate) fn plain(value: String) -> Self {
+        Self {
+            value,
+            hidden: false,
+        }
+    }
+
+    /// Создает скрываемое значение
+    pub(crate) fn hidden(value: String) -> Self {
+        Self {
+            value,
+            hidden: true,
+        }
+    }
+}
+
+/// Значения переменных, определенные при подстановке, общие для команд цепочки
+///
+/// Позволяет запрашивать значение каждой переменной один раз за весь запуск цепочки.
+#[derive(Debug, Default)]
+pub struct VariableCache {
+    /// Определенные значения по именам переменных
+    values: Mutex<HashMap<String, ResolvedVariable>>,
+}
+
+impl VariableCache {
+    /// Создает пустой кэш переменных
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Возвращает копию определенных значений
+    pub(crate) fn snapshot(&self) -> HashMap<String, ResolvedVariable> {
+        self.values
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Добавляет определенные значения
+    pub(crate) fn extend(&self, values: HashMap<String, ResolvedVariable>) {
+        self.values
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .extend(values);
+    }
+}
+
+/// Контекст выполнения команды, передаваемый цепочкой в команды
+#[derive(Clone, Copy, Default)]
+pub struct ExecutionContext<'a> {
+    /// Логгер для записи событий (если установлен)
+    pub logger: Option<&'a dyn Logger>,
+
+    /// Выводить ли команду перед выполнением (аналог `set -x`)
+    pub echo: bool,
+
+    /// Результаты ранее выполненных команд
+    ///
+    /// При последовательном выполнении цепочки содержит результаты всех
+    /// предыдущих команд, при выполнении по графу зависимостей - результаты
+    /// зависимостей команды. При параллельном выполнении пуст.
+    pub results: &'a [CommandResult],
+
+    /// Режим пробного запуска: команды не выполняются, только подставляются переменные
+    pub dry_run: bool,
+
+    /// Список для сбора идентификаторов запущенных процессов
+    pub pids: Option<&'a Mutex<Vec<u32>>>,
+
+    /// Переменные, захваченные из вывода ранее выполненных команд
+    pub variables: Option<&'a HashMap<String, String>>,
+
+    /// Результат прямой команды, передаваемый при ее откате
+    pub forward_result: Option<&'a CommandResult>,
+
+    /// Переменные окружения цепочки (переменные команды имеют приоритет)
+    pub env: Option<&'a HashMap<String, String>>,
+
+    /// Общий кэш значений переменных (если команды цепочки разделяют переменные)
+    pub variable_cache: Option<&'a VariableCache>,
+
+    /// Таймаут для команд, для которых он не задан явно
+    pub default_timeout: Option<Duration>,
+
+    /// Значения переменных, имеющие приоритет над всеми остальными источниками
+    pub overrides: Option<&'a HashMap<String, String>>,
+
+    /// Имя цепочки, выполняющей команду (для контекста сообщений лога)
+    pub chain_name: Option<&'a str>,
+}
+
+impl<'a> ExecutionContext<'a> {
+    /// Создает пустой контекст выполнения
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Устанавливает логгер
+    pub fn with_logger(mut self, logger: &'a dyn Logger) -> Self {
+        self.logger = Some(logger);
+        self
+    }
+
+    /// Устанавливает флаг вывода команд перед выполнением
+    pub fn with_echo(mut self, echo: bool) -> Self {
+        self.echo = echo;
+        self
+    }
+
+    /// Устанавливает режим пробного запуска
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Устанавливает список для сбора идентификаторов запущенных процессов
+    pub fn with_pids(mut self, pids: &'a Mutex<Vec<u32>>) -> Self {
+        self.pids = Some(pids);
+        self
+    }
+
+    /// Сохраняет идентификатор запущенного процесса
+    pub fn record_pid(&self, pid: u32) {
+        if let Some(pids) = self.pids {
+            pids.lock().unwrap_or_else(|e| e.into_inner()).push(pid);
+        }
+    }
+
+    /// Устанавливает переменные, захваченные из вывода ранее выполненных команд
+    pub fn with_variables(mut self, variables: &'a HashMap<String, String>) -> Self {
+        self.variables = Some(variables);
+        self
+    }
+
+    /// Устанавливает переменные окружения цепочки
+    pub fn with_env(mut self, env: &'a HashMap<String, String>) -> Self {
+        self.env = Some(env);
+        self
+    }
+
+    /// Устанавливает общий кэш значений переменных
+    pub fn with_variable_cache(mut self, cache: &'a VariableCache) -> Self {
+        self.variable_cache = Some(cache);
+        self
+    }
+
+    /// Устанавливает таймаут для команд, для которых он не задан явно
+    pub fn with_default_timeout(mut self, timeout: Duration) -> Self {
+        self.default_timeout = Some(timeout);
+        self
+    }
+
+    /// Устанавливает значения переменных, имеющие наивысший приоритет
+    pub fn with_overrides(mut self, overrides: &'a HashMap<String, String>) -> Self {
+        self.overrides = Some(overrides);
+        self
+    }
+
+    /// Устанавливает имя цепочки, выполняющей команду
+    pub fn with_chain_name(mut self, name: &'a str) -> Self {
+        self.chain_name = Some(name);
+        self
+    }
+
+    /// Возвращает явно заданное значение переменной
+    pub fn override_value(&self, name: &str) -> Option<&'a str> {
+        self.overrides
+            .and_then(|overrides| overrides.get(name))
+            .map(String::as_str)
+    }
+
+    /// Возвращает значение захваченной переменной
+    pub fn variable(&self, name: &str) -> Option<&'a str> {
+        self.variables
+            .and_then(|variables| variables.get(name))
+            .map(String::as_str)
+    }
+
+    /// Устанавливает результат прямой команды для ее отката
+    ///
+    /// Вывод результата подставляется в команду отката вместо `{__output__}`.
+    pub fn with_forward_result(mut self, result: &'a CommandResult) -> Self {
+        self.forward_result = Some(result);
+        self
+    }
+
+    /// Устанавливает результаты ранее выполненных команд
+    pub fn with_results(mut self, results: &'a [CommandResult]) -> Self {
+        self.results = results;
+        self
+    }
+}
+
+impl std::fmt::Debug for ExecutionContext<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExecutionContext")
+            .field("has_logger", &self.logger.is_some())
+            .field("echo", &self.echo)
+            .field("results_count", &self.results.len())
+            .field("dry_run", &self.dry_run)
+            .field("variables", &self.variables.map_or(0, HashMap::len))
+            .field("has_forward_result", &self.forward_result.is_some())
+            .field("env", &self.env.map_or(0, HashMap::len))
+            .field("has_variable_cache", &self.variable_cache.is_some())
+            .field("default_timeout", &self.default_timeout)
+            .field("overrides", &self.overrides.map_or(0, HashMap::len))
+            .field("chain_name", &self.chain_name)
+            .finish()
+    }
+}
@@ -1,7 +1,22 @@
 pub mod composite_command;
+pub mod conditional_command;
+pub mod context;
+pub mod output_parser;
+pub(crate) mod process;
+pub mod record_replay_command;
+pub mod retry;
+pub(crate) mod runtime;
+pub mod sandbox;
 pub mod shell_command;
+mod terminal;
 pub mod traits;
 
 pub use composite_command::CompositeCommand;
+pub use conditional_command::ConditionalCommand;
+pub use context::{ExecutionContext, VariableCache};
+pub use output_parser::{OutputParser, ParsedResult};
+pub use record_replay_command::{RecordReplayCommand, RecordReplayMode};
+pub use retry::RetryPolicy;
+pub use sandbox::SandboxConfig;
 pub use shell_command::ShellCommand;
-pub use traits::{Command, CommandExecution, CommandResult, ExecutionMode};
+pub use traits::{Command, CommandExecution, CommandResult, CommandStatus, ExecutionMode};
@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+use std::sync::Arc;
+
+/// Результат разбора вывода команды пользовательским обработчиком
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParsedResult {
+    /// Успешность выполнения (заменяет проверку по коду возврата)
+    pub success: bool,
+
+    /// Структурированные данные, извлеченные из вывода
+    pub data: Option<Value>,
+
+    /// Сообщение (используется как текст ошибки при неудаче)
+    pub message: Option<String>,
+}
+
+impl ParsedResult {
+    /// Создает успешный результат разбора
+    pub fn success(data: Option<Value>) -> Self {
+        Self {
+            success: true,
+            data,
+            message: None,
+        }
+    }
+
+    /// Создает неудачный результат разбора
+    pub fn failure(message: &str) -> Self {
+        Self {
+            success: false,
+            data: None,
+            message: Some(message.to_string()),
+        }
+    }
+}
+
+/// Тип функции разбора вывода: принимает вывод и код возврата
+type ParserFn = dyn Fn(&str, Option<i32>) -> ParsedResult + Send + Sync;
+
+/// Пользовательский обработчик вывода команды
+#[derive(Clone)]
+pub struct OutputParser(Arc<ParserFn>);
+
+impl OutputParser {
+    /// Создает обработчик из функции
+    pub fn new<F>(parser: F) -> Self
+    where
+        F: Fn(&str, Option<i32>) -> ParsedResult + Send + Sync + 'static,
+    {
+        Self(Arc::new(parser))
+    }
+
+    /// Разбирает вывод команды
+    pub fn parse(&self, output: &str, exit_code: Option<i32>) -> ParsedResult {
+        (self.0)(output, exit_code)
+    }
+}
+
+impl fmt::Debug for OutputParser {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("OutputParser")
+    }
+}
@@ -0,0 +1,263 @@
+use std::io;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStdin};
+use tokio::sync::mpsc::{self, UnboundedSender};
+
+use crate::logging::OutputStream;
+
+/// Максимальная длина строки, после которой она передается обработчику частями
+const MAX_LINE_BYTES: usize = 64 * 1024;
+
+/// Собранный вывод процесса
+#[derive(Debug, Default)]
+pub(crate) struct CapturedOutput {
+    /// Содержимое стандартного вывода
+    pub stdout: Vec<u8>,
+
+    /// Содержимое потока ошибок
+    pub stderr: Vec<u8>,
+
+    /// Вывод был обрезан из-за превышения ограничения размера
+    pub truncated: bool,
+}
+
+impl CapturedOutput {
+    /// Добавляет фрагмент вывода в буфер потока, не превышая ограничение размера
+    fn append(&mut self, stream: OutputStream, chunk: &[u8], max_bytes: Option<usize>) {
+        let buffer = match stream {
+            OutputStream::Stdout => &mut self.stdout,
+            OutputStream::Stderr => &mut self.stderr,
+        };
+
+        let available = match max_bytes {
+            Some(max_bytes) => max_bytes.saturating_sub(buffer.len()),
+            None => chunk.len(),
+        };
+
+        if chunk.len() > available {
+            self.truncated = true;
+        }
+
+        buffer.extend_from_slice(&chunk[..chunk.len().min(available)]);
+    }
+}
+
+/// Читает поток построчно и отправляет строки в канал
+///
+/// Строки без перевода строки длиннее `MAX_LINE_BYTES` отправляются частями,
+/// чтобы не накапливать их в памяти до конца вывода.
+async fn read_lines<R>(
+    reader: Option<R>,
+    stream: OutputStream,
+    sender: UnboundedSender<(OutputStream, Vec<u8>)>,
+) -> io::Result<()>
+where
+    R: AsyncRead + Unpin,
+{
+    let reader = match reader {
+        Some(reader) => reader,
+        None => return Ok(()),
+    };
+
+    let mut reader = BufReader::new(reader);
+    let mut line = Vec::new();
+
+    loop {
+        let buffer = reader.fill_buf().await?;
+
+        if buffer.is_empty() {
+            // Отправляем последнюю строку без завершающего перевода строки
+            if !line.is_empty() {
+                let _ = sender.send((stream, line));
+            }
+            return Ok(());
+        }
+
+        match buffer.iter().position(|byte| *byte == b'\n') {
+            Some(pos) => {
+                line.extend_from_slice(&buffer[..=pos]);
+                reader.consume(pos + 1);
+                let _ = sender.send((stream, std::mem::take(&mut line)));
+            }
+            None => {
+                let len = buffer.len();
+                line.extend_from_slice(buffer);
+                reader.consume(len);
+
+                if line.len() >= MAX_LINE_BYTES {
+                    let _ = sender.send((stream, std::mem::take(&mut line)));
+                }
+            }
+        }
+    }
+}
+
+/// Читает stdout и stderr процесса в `captured`, передавая каждую строку обработчику
+///
+/// Вывод добавляется по мере поступления, поэтому при прерывании чтения
+/// (например, по таймауту) в `captured` остается уже полученная часть.
+/// Если задан `max_bytes`, в каждом потоке сохраняется не более указанного числа байт,
+/// но чтение продолжается до завершения процесса, и обработчик получает все строки.
+pub(crate) async fn capture_output<F>(
+    child: &mut Child,
+    captured: &mut CapturedOutput,
+    max_bytes: Option<usize>,
+    mut on_line: F,
+) -> io::Result<()>
+where
+    F: FnMut(OutputStream, &str),
+{
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+
+    let stdout_reader = read_lines(child.stdout.take(), OutputStream::Stdout, sender.clone());
+    let stderr_reader = read_lines(child.stderr.take(), OutputStream::Stderr, sender);
+
+    let consumer = async {
+        while let Some((stream, chunk)) = receiver.recv().await {
+            let line = String::from_utf8_lossy(&chunk);
+            on_line(stream, line.trim_end_matches(['\n', '\r']));
+
+            captured.append(stream, &chunk, max_bytes);
+        }
+    };
+
+    let (stdout_result, stderr_result, ()) = tokio::join!(stdout_reader, stderr_reader, consumer);
+
+    stdout_result?;
+    stderr_result?;
+
+    Ok(())
+}
+
+/// Ресурсы, использованные процессом
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct ResourceUsage {
+    /// Процессорное время (пользовательское и системное) в миллисекундах
+    pub cpu_time_ms: u64,
+
+    /// Пиковый размер резидентной памяти в килобайтах
+    pub max_rss_kb: u64,
+}
+
+/// Ожидает завершения процесса и возвращает использованные им ресурсы
+///
+/// Процесс не освобождается (используется `WNOWAIT`), поэтому его код возврата
+/// затем получается обычным ожиданием `Child::wait`. Учитываются и ресурсы
+/// дочерних процессов, завершение которых процесс ожидал.
+#[cfg(target_os = "linux")]
+pub(crate) async fn wait_resource_usage(pid: Option<u32>) -> Option<ResourceUsage> {
+    let pid = pid? as libc::id_t;
+
+    tokio::task::spawn_blocking(move || {
+        // Безопасно: структуры инициализированы нулями и живут до конца вызова
+        let mut info: libc::siginfo_t = unsafe { std::mem::zeroed() };
+        let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+
+        // Обертка waitid из libc не передает rusage, поэтому используется системный вызов
+        let ret = unsafe {
+            libc::syscall(
+                libc::SYS_waitid,
+                libc::P_PID,
+                pid,
+                &mut info as *mut libc::siginfo_t,
+                libc::WEXITED | libc::WNOWAIT,
+                &mut usage as *mut libc::rusage,
+            )
+        };
+
+        if ret != 0 {
+            return None;
+        }
+
+        let to_ms = |time: libc::timeval| time.tv_sec as u64 * 1000 + time.tv_usec as u64 / 1000;
+
+        Some(ResourceUsage {
+            cpu_time_ms: to_ms(usage.ru_utime) + to_ms(usage.ru_stime),
+            max_rss_kb: usage.ru_maxrss as u64,
+        })
+    })
+    .await
+    .ok()
+    .flatten()
+}
+
+/// Ожидает завершения процесса и возвращает использованные им ресурсы
+///
+/// На этой платформе сведения о ресурсах недоступны.
+#[cfg(not(target_os = "linux"))]
+pub(crate) async fn wait_resource_usage(_pid: Option<u32>) -> Option<ResourceUsage> {
+    None
+}
+
+/// Записывает данные во входной поток процесса и закрывает его
+pub(crate) async fn write_stdin(stdin: Option<ChildStdin>, data: Option<&str>) -> io::Result<()> {
+    let (mut stdin, data) = match (stdin, data) {
+        (Some(stdin), Some(data)) => (stdin, data),
+        _ => return Ok(()),
+    };
+
+    match stdin.write_all(data.as_bytes()).await {
+        // Процесс может завершиться, не прочитав весь ввод
+        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        result => result,
+    }
+}
+
+/// Завершает процесс: сначала SIGTERM, затем SIGKILL, если процесс не завершился за `grace`
+///
+/// Без периода ожидания процесс завершается сразу.
+#[cfg(unix)]
+pub(crate) async fn terminate(child: &mut Child, grace: Option<Duration>) {
+    if let (Some(grace), Some(pid)) = (grace, child.id()) {
+        // Безопасно: сигнал отправляется еще не освобожденному дочернему процессу
+        let sent = unsafe { libc::kill(pid as libc::pid_t, libc::SIGTERM) } == 0;
+
+        if sent && tokio::time::timeout(grace, child.wait()).await.is_ok() {
+            return;
+        }
+    }
+
+    let _ = child.kill().await;
+}
+
+/// Завершает процесс (на этой платформе период ожидания не поддерживается)
+#[cfg(not(unix))]
+pub(crate) async fn terminate(child: &mut Child, _grace: Option<Duration>) {
+    let _ = child.kill().await;
+}
+
+/// Возвращает доступный интерпретатор PowerShell: `pwsh`, если он установлен,
+/// иначе `powershell`
+pub(crate) fn powershell_program() -> &'static str {
+    if find_program("pwsh").is_some() {
+        "pwsh"
+    } else {
+        "powershell"
+    }
+}
+
+/// Ищет исполняемый файл программы (по пути или в каталогах `PATH`)
+pub(crate) fn find_program(program: &str) -> Option<std::path::PathBuf> {
+    let path = std::path::Path::new(program);
+
+    if path.components().count() > 1 {
+        return path.is_file().then(|| path.to_path_buf());
+    }
+
+    let paths = std::env::var_os("PATH")?;
+
+    std::env::split_paths(&paths).find_map(|dir| {
+        let candidate = dir.join(program);
+
+        #[cfg(target_family = "windows")]
+        if candidate.extension().is_none() {
+            let exe = candidate.with_extension("exe");
+            if exe.is_file() {
+                return Some(exe);
+            }
+        }
+
+        candidate.is_file().then_some(candidate)
+    })
+}
@@ -0,0 +1,221 @@
+use async_trait::async_trait;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use tokio::sync::Mutex;
+
+use crate::command::context::{ExecutionContext, VariableCache};
+use crate::command::shell_command::ShellCommand;
+use crate::command::traits::{
+    Command, CommandError, CommandExecution, CommandResult, ExecutionMode,
+};
+use crate::visitor::Visitor;
+
+lazy_static! {
+    /// Блокировка для синхронизации доступа к файлам записей
+    static ref RECORDINGS_LOCK: Mutex<()> = Mutex::new(());
+}
+
+/// Режим записи и воспроизведения
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RecordReplayMode {
+    /// Всегда выполнять команду и перезаписывать результат
+    Record,
+    /// Только воспроизводить сохраненный результат
+    Replay,
+    /// Воспроизводить, если запись есть, иначе выполнить и записать
+    Auto,
+}
+
+/// Команда с записью результатов выполнения и их последующим воспроизведением
+#[derive(Debug, Clone)]
+pub struct RecordReplayCommand {
+    /// Оборачиваемая команда
+    command: ShellCommand,
+
+    /// Путь к файлу с записями результатов
+    recordings_file: String,
+
+    /// Режим работы
+    mode: RecordReplayMode,
+
+    /// Записывать ли неуспешные результаты
+    record_failures: bool,
+}
+
+impl RecordReplayCommand {
+    /// Создает новую команду с записью результатов в указанный файл
+    pub fn new(command: ShellCommand, recordings_file: &str) -> Self {
+        Self {
+            command,
+            recordings_file: recordings_file.to_string(),
+            mode: RecordReplayMode::Auto,
+            record_failures: false,
+        }
+    }
+
+    /// Устанавливает режим работы
+    pub fn with_mode(mut self, mode: RecordReplayMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Устанавливает флаг записи неуспешных результатов (по умолчанию выключен)
+    ///
+    /// Без него неуспешный результат не сохраняется, и в режиме `Auto` команда
+    /// выполняется снова при следующем запуске.
+    pub fn with_record_failures(mut self, record: bool) -> Self {
+        self.record_failures = record;
+        self
+    }
+
+    /// Возвращает ключ, под которым сохраняется результат команды
+    ///
+    /// Ключ вычисляется по командной строке после подстановки переменных и
+    /// действующему окружению (включая окружение цепочки из контекста).
+    pub async fn cache_key(&self, context: &ExecutionContext<'_>) -> Result<String, CommandError> {
+        self.command.cache_key(context).await
+    }
+
+    /// Загружает записи из файла (отсутствующий файл означает пустой набор)
+    async fn load_recordings(&self) -> Result<HashMap<String, CommandResult>, CommandError> {
+        if !Path::new(&self.recordings_file).exists() {
+            return Ok(HashMap::new());
+        }
+
+        let contents = tokio::fs::read_to_string(&self.recordings_file)
+            .await
+            .map_err(|e| {
+                CommandError::ExecutionError(format!("Не удалось прочитать файл записей: {}", e))
+            })?;
+
+        serde_json::from_str(&contents).map_err(|e| {
+            CommandError::ExecutionError(format!("Не удалось разобрать файл записей: {}", e))
+        })
+    }
+
+    /// Сохраняет результат команды в файл записей
+    async fn save_recording(
+        &self,
+        key: String,
+        result: &CommandResult,
+    ) -> Result<(), CommandError> {
+        let _lock = RECORDINGS_LOCK.lock().await;
+
+        let mut recordings = self.load_recordings().await?;
+        recordings.insert(key, result.clone());
+
+        let contents = serde_json::to_string_pretty(&recordings).map_err(|e| {
+            CommandError::ExecutionError(format!("Не удалось сериализовать записи: {}", e))
+        })?;
+
+        tokio::fs::write(&self.recordings_file, contents)
+            .await
+            .map_err(|e| {
+                CommandError::ExecutionError(format!("Не удалось записать файл записей: {}", e))
+            })
+    }
+
+    /// Ищет сохраненный результат команды
+    async fn find_recording(&self, key: &str) -> Result<Option<CommandResult>, CommandError> {
+        let _lock = RECORDINGS_LOCK.lock().await;
+        let mut recordings = self.load_recordings().await?;
+
+        Ok(recordings.remove(key))
+    }
+}
+
+#[async_trait]
+impl CommandExecution for RecordReplayCommand {
+    async fn execute(&self) -> Result<CommandResult, CommandError> {
+        self.execute_with_context(&ExecutionContext::default())
+            .await
+    }
+
+    async fn execute_with_context(
+        &self,
+        context: &ExecutionContext<'_>,
+    ) -> Result<CommandResult, CommandError> {
+        // Результаты пробного запуска не записываются и не воспроизводятся
+        if context.dry_run {
+            return self.command.execute_with_context(context).await;
+        }
+
+        // Значения переменных определяются один раз для ключа и выполнения
+        let cache = VariableCache::new();
+        let context = match context.variable_cache {
+            Some(_) => *context,
+            None => context.with_variable_cache(&cache),
+        };
+        let key = self.cache_key(&context).await?;
+
+        if self.mode != RecordReplayMode::Record {
+            if let Some(result) = self.find_recording(&key).await? {
+                return Ok(result);
+            }
+
+            if self.mode == RecordReplayMode::Replay {
+                return Err(CommandError::ExecutionError(format!(
+                    "Запись результата для команды '{}' не найдена в {}",
+                    self.name(),
+                    self.recordings_file
+                )));
+            }
+        }
+
+        let result = self.command.execute_with_context(&context).await?;
+
+        if result.success || self.record_failures {
+            self.save_recording(key, &result).await?;
+        }
+
+        Ok(result)
+    }
+
+    async fn rollback(&self) -> Result<CommandResult, CommandError> {
+        self.command.rollback().await
+    }
+
+    async fn rollback_with_context(
+        &self,
+        context: &ExecutionContext<'_>,
+    ) -> Result<CommandResult, CommandError> {
+        self.command.rollback_with_context(context).await
+    }
+
+    fn name(&self) -> &str {
+        self.command.name()
+    }
+
+    fn execution_mode(&self) -> ExecutionMode {
+        self.command.execution_mode()
+    }
+
+    fn supports_rollback(&self) -> bool {
+        self.command.supports_rollback()
+    }
+
+    fn skip_rollback(&self) -> bool {
+        self.command.skip_rollback()
+    }
+
+    fn dependencies(&self) -> Vec<String> {
+        self.command.dependencies()
+    }
+
+    fn priority(&self) -> i32 {
+        self.command.priority()
+    }
+
+    fn rollback_preview(&self) -> Option<String> {
+        self.command.rollback_preview()
+    }
+}
+
+#[async_trait]
+impl Command for RecordReplayCommand {
+    fn accept(&self, visitor: &mut dyn Visitor) {
+        self.command.accept(visitor);
+    }
+}
@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Политика задержек между повторными попытками (экспоненциальная, с необязательным разбросом)
+///
+/// Задержка перед попыткой `n` (начиная с 1) равна `base * 2^(n-1)`, но не больше `max`.
+/// Разброс выбирает задержку случайно в пределах от половины до полуторной
+/// вычисленной задержки, чтобы параллельные команды не повторялись одновременно.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetryPolicy {
+    /// Задержка перед первой повторной попыткой
+    pub base: Duration,
+
+    /// Максимальная задержка
+    pub max: Duration,
+
+    /// Случайный разброс задержки
+    pub jitter: bool,
+}
+
+impl RetryPolicy {
+    /// Создает политику без разброса
+    pub fn new(base: Duration, max: Duration) -> Self {
+        Self {
+            base,
+            max,
+            jitter: false,
+        }
+    }
+
+    /// Включает или выключает случайный разброс задержки
+    pub fn with_jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Возвращает задержку перед повторной попыткой с номером `attempt` (начиная с 1)
+    pub fn delay(&self, attempt: u32) -> Duration {
+        self.delay_with(attempt, random_fraction)
+    }
+
+    /// Возвращает задержку, используя указанный источник случайных чисел из `[0, 1)`
+    ///
+    /// Источник вызывается только при включенном разбросе.
+    pub fn delay_with<R>(&self, attempt: u32, mut random: R) -> Duration
+    where
+        R: FnMut() -> f64,
+    {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        let backoff = self.base.saturating_mul(factor).min(self.max);
+
+        if !self.jitter {
+            return backoff;
+        }
+
+        // Умножение с проверкой: при задержке, близкой к `Duration::MAX`, результат
+        // может не поместиться в `Duration` и тогда ограничивается максимумом
+        let factor = 0.5 + random().clamp(0.0, 1.0);
+        Duration::try_from_secs_f64(backoff.as_secs_f64() * factor)
+            .map_or(self.max, |delay| delay.min(self.max))
+    }
+}
+
+/// Возвращает случайное число из `[0, 1)`
+fn random_fraction() -> f64 {
+    // Младшие 53 бита UUID v4 случайны, что избавляет от отдельной зависимости генератора
+    let bits = uuid::Uuid::new_v4().as_u128() as u64 & ((1u64 << 53) - 1);
+    bits as f64 / (1u64 << 53) as f64
+}
@@ -0,0 +1,27 @@
+use std::future::Future;
+
+use crate::command::traits::CommandError;
+
+/// Выполняет future до завершения в собственной однопоточной среде tokio
+///
+/// Используется синхронными методами `execute_blocking`. Вызов из среды
+/// выполнения tokio завершается ошибкой, так как блокирование ее потока
+/// приводит к взаимной блокировке.
+pub(crate) fn block_on<F, T>(future: F) -> Result<T, CommandError>
+where
+    F: Future<Output = Result<T, CommandError>>,
+{
+    if tokio::runtime::Handle::try_current().is_ok() {
+        return Err(CommandError::ExecutionError(
+            "execute_blocking нельзя вызывать внутри среды выполнения tokio, используйте execute"
+                .to_string(),
+        ));
+    }
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(CommandError::IoError)?;
+
+    runtime.block_on(future)
+}
@@ -0,0 +1,86 @@
+use serde::{Deserialize, Serialize};
+
+use crate::command::process;
+use crate::command::traits::CommandError;
+
+/// Программа, используемая для изоляции команд (bubblewrap)
+const SANDBOX_PROGRAM: &str = "bwrap";
+
+/// Настройки изоляции команды (только Linux, через bubblewrap)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SandboxConfig {
+    /// Запретить доступ к сети
+    pub no_network: bool,
+
+    /// Смонтировать корневую файловую систему только для чтения
+    pub read_only_root: bool,
+
+    /// Использовать отдельный пустой /tmp
+    pub private_tmp: bool,
+}
+
+impl SandboxConfig {
+    /// Создает настройки изоляции без ограничений
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Запрещает доступ к сети
+    pub fn no_network(mut self) -> Self {
+        self.no_network = true;
+        self
+    }
+
+    /// Монтирует корневую файловую систему только для чтения
+    pub fn read_only_root(mut self) -> Self {
+        self.read_only_root = true;
+        self
+    }
+
+    /// Использует отдельный пустой /tmp
+    pub fn private_tmp(mut self) -> Self {
+        self.private_tmp = true;
+        self
+    }
+
+    /// Проверяет, доступна ли изоляция на текущей платформе
+    pub fn is_supported() -> bool {
+        cfg!(target_os = "linux") && process::find_program(SANDBOX_PROGRAM).is_some()
+    }
+
+    /// Возвращает ошибку о недоступности изоляции
+    pub(crate) fn unsupported_error() -> CommandError {
+        CommandError::ExecutionError(format!(
+            "Изоляция команд не поддерживается: требуется Linux и установленный {}",
+            SANDBOX_PROGRAM
+        ))
+    }
+
+    /// Возвращает программу и аргументы для запуска команды в песочнице
+    pub(crate) fn wrap(&self, program: &str, args: &[&str]) -> (String, Vec<String>) {
+        let mut wrapped: Vec<String> = Vec::new();
+
+        let root_bind = if self.read_only_root {
+            "--ro-bind"
+        } else {
+            "--bind"
+        };
+        wrapped.extend([root_bind, "/", "/"].map(String::from));
+        wrapped.extend(["--dev", "/dev", "--proc", "/proc"].map(String::from));
+
+        if self.private_tmp {
+            wrapped.extend(["--tmpfs", "/tmp"].map(String::from));
+        }
+
+        if self.no_network {
+            wrapped.push("--unshare-net".to_string());
+        }
+
+        wrapped.push("--die-with-parent".to_string());
+        wrapped.push("--".to_string());
+        wrapped.push(program.to_string());
+        wrapped.extend(args.iter().map(|arg| arg.to_string()));
+
+        (SANDBOX_PROGRAM.to_string(), wrapped)
+    }
+}
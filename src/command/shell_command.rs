@@ -3,27 +3,47 @@ use lazy_static::lazy_static;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use sha2::{Digest, Sha256};
 use shlex::split;
 use std::collections::HashMap;
 use std::env;
 use std::io::{self as stdio, BufRead};
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
 use tokio::fs::File;
 use tokio::io::AsyncReadExt;
 use tokio::io::{self, AsyncWriteExt};
 use tokio::process::Command as TokioCommand;
 
+use crate::command::context::{ExecutionContext, ResolvedVariable, VariableCache};
+use crate::command::output_parser::{OutputParser, ParsedResult};
+use crate::command::process::{self, CapturedOutput};
+use crate::command::retry::RetryPolicy;
+use crate::command::runtime;
+use crate::command::sandbox::SandboxConfig;
+use crate::command::terminal;
 use crate::command::traits::{
     Command, CommandError, CommandExecution, CommandResult, ExecutionMode,
 };
+use crate::logging::{LogContext, LogLevel, Logger, OutputStream};
 use crate::visitor::Visitor;
 
 lazy_static! {
     static ref VAR_PATTERN: Regex = Regex::new(r"\{([^{}]+)\}").unwrap();
     static ref ENV_VAR_PATTERN: Regex = Regex::new(r"\{\$([^{}]+)\}").unwrap();
     static ref FILE_VAR_PATTERN: Regex = Regex::new(r"\{#([^{}]+)\}").unwrap();
-    static ref INTERACTIVE_VAR_PATTERN: Regex = Regex::new(r"\{([^$#{}][^{}]*)\}").unwrap();
+    static ref SECRET_VAR_PATTERN: Regex = Regex::new(r"\{!([^{}]+)\}").unwrap();
+    static ref INTERACTIVE_VAR_PATTERN: Regex = Regex::new(r"\{([^$#!{}][^{}]*)\}").unwrap();
+    static ref WINDOWS_ENV_PATTERN: Regex = Regex::new(r"%([^%\s=]+)%").unwrap();
 }
 
+/// Подстановка вывода прямой команды в команде отката
+const OUTPUT_PLACEHOLDER: &str = "{__output__}";
+
+/// Значение, которым заменяются скрытые переменные в отображаемой командной строке
+const REDACTED_VALUE: &str = "***";
+
 /// Структура для выполнения команд в оболочке
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ShellCommand {
@@ -36,9 +56,25 @@ pub struct ShellCommand {
     /// Рабочая директория для выполнения команды
     working_dir: Option<String>,
 
+    /// Создавать ли рабочую директорию, если она не существует
+    #[serde(default)]
+    create_working_dir: bool,
+
     /// Переменные окружения
     env_vars: HashMap<String, String>,
 
+    /// Не наследовать окружение родительского процесса
+    #[serde(default)]
+    clean_env: bool,
+
+    /// Наследуемые переменные окружения, удаляемые перед запуском
+    #[serde(default)]
+    env_remove: Vec<String>,
+
+    /// Путь к файлу с переменными окружения в формате `.env`
+    #[serde(default)]
+    env_file: Option<String>,
+
     /// Режим выполнения
     mode: ExecutionMode,
 
@@ -48,11 +84,133 @@ pub struct ShellCommand {
     /// Команда для отката
     rollback_command: Option<String>,
 
-    /// Таймаут выполнения команды в секундах
-    timeout_seconds: Option<u64>,
+    /// Таймаут выполнения команды
+    timeout: Option<Duration>,
 
     /// Путь к файлу с переменными
     variables_file: Option<String>,
+
+    /// Выводить ли команду перед выполнением
+    #[serde(default)]
+    echo: bool,
+
+    /// Скрывать ли интерактивно введенные значения в отображаемой командной строке
+    #[serde(default)]
+    redact_variables: bool,
+
+    /// Пользовательский обработчик вывода команды
+    #[serde(skip)]
+    output_parser: Option<OutputParser>,
+
+    /// Маска прав создаваемых файлов (только Unix)
+    #[serde(default)]
+    umask: Option<u32>,
+
+    /// Коды возврата, считающиеся успешными
+    #[serde(default = "default_success_codes")]
+    success_codes: Vec<i32>,
+
+    /// Настройки изоляции команды
+    #[serde(default)]
+    sandbox: Option<SandboxConfig>,
+
+    /// Выполнять ли команду без изоляции, если она не поддерживается
+    #[serde(default)]
+    sandbox_optional: bool,
+
+    /// Данные, передаваемые команде на стандартный ввод
+    #[serde(default)]
+    stdin: Option<String>,
+
+    /// Интервал периодического логирования выполнения команды
+    #[serde(default)]
+    heartbeat: Option<Duration>,
+
+    /// Исключена ли команда из отката цепочки
+    #[serde(default)]
+    skip_rollback: bool,
+
+    /// Имя переменной, в которую сохраняется вывод команды
+    #[serde(default)]
+    capture_as: Option<String>,
+
+    /// Имена команд, от которых зависит команда (режим DAG)
+    #[serde(default)]
+    depends_on: Vec<String>,
+
+    /// Максимальный размер сохраняемого вывода каждого потока в байтах
+    #[serde(default)]
+    max_output_bytes: Option<usize>,
+
+    /// Интерпретатор и флаг для передачи ему командной строки (по умолчанию `sh -c` / `cmd.exe /C`)
+    #[serde(default)]
+    shell: Option<(String, String)>,
+
+    /// Произвольные метки команды (команда, критичность, номер задачи и т.п.)
+    #[serde(default)]
+    tags: HashMap<String, String>,
+
+    /// Постоянный идентификатор результатов команды (вместо случайного UUID)
+    #[serde(default)]
+    id: Option<String>,
+
+    /// Сохранять стандартный вывод без декодирования в `CommandResult::stdout_bytes`
+    #[serde(default)]
+    binary_output: bool,
+
+    /// Условие повторного выполнения до совпадения вывода с шаблоном
+    #[serde(skip)]
+    wait_until: Option<WaitCondition>,
+
+    /// Подстрока, которую должен содержать вывод успешной команды
+    #[serde(default)]
+    expect_output_contains: Option<String>,
+
+    /// Шаблон, с которым должен совпадать вывод успешной команды
+    #[serde(skip)]
+    expect_output_matches: Option<Regex>,
+
+    /// Количество повторных попыток неудачного отката
+    #[serde(default)]
+    rollback_retries: u32,
+
+    /// Задержки между повторными попытками отката (по умолчанию без задержки)
+    #[serde(default)]
+    rollback_retry_policy: Option<RetryPolicy>,
+
+    /// Команда, выполняемая только при ошибке этой команды
+    #[serde(default)]
+    on_error: Option<String>,
+
+    /// Время между SIGTERM и SIGKILL при завершении команды по таймауту (только Unix)
+    #[serde(default)]
+    kill_grace: Option<Duration>,
+
+    /// Приоритет запуска при параллельном выполнении с ограничением числа одновременных команд
+    #[serde(default)]
+    priority: i32,
+
+    /// Подставлять ли переменные окружения `%VAR%` до запуска интерпретатора
+    #[serde(default)]
+    windows_env_expansion: bool,
+}
+
+/// Условие ожидания: команда повторяется, пока ее вывод не совпадет с шаблоном
+#[derive(Debug, Clone)]
+struct WaitCondition {
+    /// Шаблон, с которым должен совпасть стандартный вывод
+    pattern: Regex,
+
+    /// Интервал между попытками
+    interval: Duration,
+
+    /// Максимальное количество попыток
+    max_attempts: u32,
+}
+
+/// Коды возврата, считающиеся успешными по умолчанию
+fn default_success_codes() -> Vec<i32> {
+    vec![0]
 }
 
 impl ShellCommand {
@@ -62,12 +220,42 @@ impl ShellCommand {
             name: name.to_string(),
             command: command.to_string(),
             working_dir: None,
+            create_working_dir: false,
             env_vars: HashMap::new(),
+            clean_env: false,
+            env_remove: Vec::new(),
+            env_file: None,
             mode: ExecutionMode::Sequential,
             supports_rollback: false,
             rollback_command: None,
-            timeout_seconds: None,
+            timeout: None,
             variables_file: None,
+            echo: false,
+            redact_variables: false,
+            output_parser: None,
+            umask: None,
+            success_codes: default_success_codes(),
+            sandbox: None,
+            sandbox_optional: false,
+            stdin: None,
+            heartbeat: None,
+            skip_rollback: false,
+            capture_as: None,
+            depends_on: Vec::new(),
+            max_output_bytes: None,
+            shell: None,
+            tags: HashMap::new(),
+            id: None,
+            binary_output: false,
+            wait_until: None,
+            expect_output_contains: None,
+            expect_output_matches: None,
+            rollback_retries: 0,
+            rollback_retry_policy: None,
+            on_error: None,
+            kill_grace: None,
+            priority: 0,
+            windows_env_expansion: false,
         }
     }
 
@@ -77,12 +265,154 @@ impl ShellCommand {
         self
     }
 
+    /// Устанавливает флаг создания рабочей директории (со всеми родительскими),
+    /// если она не существует
+    pub fn with_create_working_dir(mut self, create: bool) -> Self {
+        self.create_working_dir = create;
+        self
+    }
+
     /// Добавляет переменную окружения
     pub fn with_env_var(mut self, key: &str, value: &str) -> Self {
         self.env_vars.insert(key.to_string(), value.to_string());
         self
     }
 
+    /// Устанавливает флаг запуска без наследования окружения родительского процесса
+    ///
+    /// Команде будут доступны только переменные, заданные через `with_env_var`.
+    pub fn with_clean_env(mut self, clean: bool) -> Self {
+        self.clean_env = clean;
+        self
+    }
+
+    /// Удаляет наследуемую переменную окружения перед запуском команды
+    pub fn with_env_remove(mut self, key: &str) -> Self {
+        self.env_remove.push(key.to_string());
+        self
+    }
+
+    /// Устанавливает файл с переменными окружения в формате `.env`
+    ///
+    /// Файл читается при каждом запуске команды. Переменные, заданные через
+    /// `with_env_var`, имеют приоритет над значениями из файла.
+    pub fn with_env_file(mut self, path: &str) -> Self {
+        self.env_file = Some(path.to_string());
+        self
+    }
+
+    /// Добавляет метку команды
+    pub fn with_tag(mut self, key: &str, value: &str) -> Self {
+        self.tags.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    /// Устанавливает постоянный идентификатор результатов команды
+    ///
+    /// Результаты всех запусков команды получат этот идентификатор вместо
+    /// случайного UUID, что упрощает сопоставление записей логов.
+    pub fn with_id(mut self, id: &str) -> Self {
+        self.id = Some(id.to_string());
+        self
+    }
+
+    /// Возвращает исходную командную строку (до подстановки переменных)
+    pub fn command(&self) -> &str {
+        &self.command
+    }
+
+    /// Возвращает метки команды
+    pub fn tags(&self) -> &HashMap<String, String> {
+        &self.tags
+    }
+
+    /// Возвращает рабочую директорию команды (если задана)
+    pub fn working_dir(&self) -> Option<&str> {
+        self.working_dir.as_deref()
+    }
+
+    /// Возвращает таймаут выполнения команды (если задан)
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Возвращает переменные окружения, заданные для команды
+    pub fn env_vars(&self) -> &HashMap<String, String> {
+        &self.env_vars
+    }
+
+    /// Возвращает команду отката (если задана)
+    pub fn rollback_command(&self) -> Option<&str> {
+        self.rollback_command.as_deref()
+    }
+
+    /// Проверяет подстановки переменных в команде, команде отката и команде `on_error`
+    ///
+    /// Возвращает ошибку с позицией (номером символа, начиная с 1) первой незакрытой
+    /// или лишней фигурной скобки либо пустой подстановки вида `{}`. Такие подстановки
+    /// не распознаются и передаются интерпретатору как есть.
+    pub fn validate(&self) -> Result<(), CommandError> {
+        match self.placeholder_problem() {
+            Some(problem) => Err(CommandError::ExecutionError(problem)),
+            None => Ok(()),
+        }
+    }
+
+    /// Возвращает описание первой некорректной подстановки переменных (если есть)
+    pub(crate) fn placeholder_problem(&self) -> Option<String> {
+        let lines = [
+            ("команде", Some(self.command.as_str())),
+            ("команде отката", self.rollback_command.as_deref()),
+            ("команде on_error", self.on_error.as_deref()),
+        ];
+
+        lines.into_iter().find_map(|(kind, line)| {
+            line.and_then(Self::check_placeholders).map(|problem| {
+                format!(
+                    "Некорректная подстановка в {} '{}': {}",
+                    kind, self.name, problem
+                )
+            })
+        })
+    }
+
+    /// Ищет в строке несбалансированные фигурные скобки и пустые подстановки
+    fn check_placeholders(line: &str) -> Option<String> {
+        // Позиция открывающей скобки текущей подстановки
+        let mut open: Option<usize> = None;
+
+        for (index, ch) in line.chars().enumerate() {
+            let position = index + 1;
+
+            match (ch, open) {
+                ('{', Some(start)) => {
+                    return Some(format!("незакрытая фигурная скобка в позиции {}", start));
+                }
+                ('{', None) => open = Some(position),
+                ('}', None) => {
+                    return Some(format!("лишняя закрывающая скобка в позиции {}", position));
+                }
+                ('}', Some(start)) => {
+                    let name: String = line
+                        .chars()
+                        .skip(start)
+                        .take(position - start - 1)
+                        .collect();
+                    let name = name.trim_start_matches(['$', '#', '!']);
+
+                    if name.trim().is_empty() {
+                        return Some(format!("пустая подстановка в позиции {}", start));
+                    }
+
+                    open = None;
+                }
+                _ => {}
+            }
+        }
+
+        open.map(|start| format!("незакрытая фигурная скобка в позиции {}", start))
+    }
+
     /// Устанавливает режим выполнения
     pub fn with_execution_mode(mut self, mode: ExecutionMode) -> Self {
         self.mode = mode;
@@ -96,9 +426,53 @@ impl ShellCommand {
         self
     }
 
+    /// Исключает команду из отката цепочки, даже если команда отката задана
+    pub fn with_skip_rollback(mut self, skip: bool) -> Self {
+        self.skip_rollback = skip;
+        self
+    }
+
+    /// Устанавливает таймаут выполнения в секундах
+    pub fn with_timeout(self, seconds: u64) -> Self {
+        self.with_timeout_duration(Duration::from_secs(seconds))
+    }
+
     /// Устанавливает таймаут выполнения
-    pub fn with_timeout(mut self, seconds: u64) -> Self {
-        self.timeout_seconds = Some(seconds);
+    pub fn with_timeout_duration(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Устанавливает время ожидания завершения команды после SIGTERM при таймауте
+    ///
+    /// По таймауту команде отправляется SIGTERM, а если она не завершилась за
+    /// указанное время — SIGKILL. Без этой настройки, а также не на Unix, команда
+    /// завершается сразу.
+    pub fn with_kill_grace(mut self, grace: Duration) -> Self {
+        self.kill_grace = Some(grace);
+        self
+    }
+
+    /// Устанавливает приоритет запуска (по умолчанию 0)
+    ///
+    /// При параллельном выполнении с ограничением числа одновременных команд
+    /// команды с большим приоритетом запускаются раньше; команды с равным
+    /// приоритетом — в порядке добавления.
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Включает подстановку переменных окружения вида `%VAR%` самой библиотекой
+    ///
+    /// Нужна для интерпретаторов, которые не раскрывают `%VAR%` (например,
+    /// PowerShell). Значения берутся из переменных команды, файла окружения,
+    /// окружения цепочки и окружения процесса (в порядке убывания приоритета);
+    /// неизвестные переменные остаются как есть. На Windows имена сравниваются
+    /// без учета регистра, но точное совпадение имени имеет приоритет. По умолчанию выключена, и
+    /// раскрытие `%VAR%` остается за `cmd.exe`. Работает на любой платформе.
+    pub fn with_windows_env_expansion(mut self, enabled: bool) -> Self {
+        self.windows_env_expansion = enabled;
         self
     }
 
@@ -108,20 +482,327 @@ impl ShellCommand {
         self
     }
 
+    /// Устанавливает флаг вывода команды перед выполнением
+    ///
+    /// Строки вывода команды при этом передаются логгеру контекста
+    /// (`Logger::log_output`) по мере поступления.
+    pub fn with_echo(mut self, echo: bool) -> Self {
+        self.echo = echo;
+        self
+    }
+
+    /// Устанавливает флаг скрытия интерактивно введенных значений
+    ///
+    /// Значения заменяются на `***` в выводе команды перед выполнением
+    /// и в поле `resolved_command` результата.
+    pub fn with_redact_variables(mut self, redact: bool) -> Self {
+        self.redact_variables = redact;
+        self
+    }
+
+    /// Повторяет команду, пока ее вывод не совпадет с шаблоном
+    ///
+    /// Команда выполняется не более `max_attempts` раз с интервалом `interval` и
+    /// считается успешной, как только она завершилась успешно и ее стандартный
+    /// вывод совпал с шаблоном. Если попытки исчерпаны, результат отмечается
+    /// как прерванный по таймауту (`CommandStatus::TimedOut`). Интерактивные
+    /// переменные запрашиваются только при первой попытке.
+    pub fn with_wait_until(
+        mut self,
+        pattern: Regex,
+        interval: Duration,
+        max_attempts: u32,
+    ) -> Self {
+        self.wait_until = Some(WaitCondition {
+            pattern,
+            interval,
+            max_attempts: max_attempts.max(1),
+        });
+        self
+    }
+
+    /// Требует, чтобы вывод команды содержал подстроку
+    ///
+    /// Если команда завершилась успешно, но вывод не содержит подстроку,
+    /// результат отмечается как неудачный.
+    pub fn with_expect_output_contains(mut self, expected: &str) -> Self {
+        self.expect_output_contains = Some(expected.to_string());
+        self
+    }
+
+    /// Требует, чтобы вывод команды совпадал с шаблоном
+    ///
+    /// Если команда завершилась успешно, но вывод не совпал с шаблоном,
+    /// результат отмечается как неудачный.
+    pub fn with_expect_output_matches(mut self, pattern: Regex) -> Self {
+        self.expect_output_matches = Some(pattern);
+        self
+    }
+
+    /// Устанавливает обработчик вывода, определяющий успешность и данные результата
+    pub fn with_output_parser<F>(self, parser: F) -> Self
+    where
+        F: Fn(&str, Option<i32>) -> ParsedResult + Send + Sync + 'static,
+    {
+        self.with_parser(OutputParser::new(parser))
+    }
+
+    /// Устанавливает готовый обработчик вывода
+    pub(crate) fn with_parser(mut self, parser: OutputParser) -> Self {
+        self.output_parser = Some(parser);
+        self
+    }
+
+    /// Устанавливает маску прав создаваемых файлов (на Windows игнорируется)
+    pub fn with_umask(mut self, mask: u32) -> Self {
+        self.umask = Some(mask);
+        self
+    }
+
+    /// Устанавливает коды возврата, считающиеся успешными (по умолчанию только 0)
+    pub fn with_success_codes(mut self, codes: Vec<i32>) -> Self {
+        self.success_codes = codes;
+        self
+    }
+
+    /// Устанавливает изоляцию команды (только Linux, требуется bubblewrap)
+    pub fn with_sandbox(mut self, config: SandboxConfig) -> Self {
+        self.sandbox = Some(config);
+        self
+    }
+
+    /// Разрешает выполнение без изоляции на платформах, где она не поддерживается
+    pub fn with_sandbox_optional(mut self, optional: bool) -> Self {
+        self.sandbox_optional = optional;
+        self
+    }
+
+    /// Устанавливает данные, передаваемые команде на стандартный ввод
+    pub fn with_stdin(mut self, data: impl Into<String>) -> Self {
+        self.stdin = Some(data.into());
+        self
+    }
+
+    /// Устанавливает количество повторных попыток отката
+    ///
+    /// Если команда отката завершилась неудачно, она повторяется до `retries` раз;
+    /// возвращается результат последней попытки.
+    pub fn with_rollback_retries(mut self, retries: u32) -> Self {
+        self.rollback_retries = retries;
+        self
+    }
+
+    /// Устанавливает задержки между повторными попытками отката
+    pub fn with_rollback_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.rollback_retry_policy = Some(policy);
+        self
+    }
+
+    /// Устанавливает команду, выполняемую при ошибке этой команды
+    ///
+    /// В отличие от команды отката, она не выполняется при откате цепочки из-за
+    /// ошибки другой команды. Результат команды не меняется; ошибка самой команды
+    /// `on_error` только логируется.
+    pub fn with_on_error(mut self, command: &str) -> Self {
+        self.on_error = Some(command.to_string());
+        self
+    }
+
+    /// Устанавливает интервал периодического логирования выполнения команды
+    ///
+    /// Пока команда выполняется, через логгер контекста выполнения с указанным
+    /// интервалом записывается сообщение о том, сколько времени прошло.
+    pub fn with_heartbeat(mut self, interval: Duration) -> Self {
+        self.heartbeat = Some(interval);
+        self
+    }
+
+    /// Сохраняет вывод команды (без пробельных символов по краям) в переменную
+    ///
+    /// Захваченная переменная доступна как `{name}` в команде отката, а при
+    /// последовательном выполнении цепочки — и в следующих командах.
+    pub fn with_capture_as(mut self, name: &str) -> Self {
+        self.capture_as = Some(name.to_string());
+        self
+    }
+
+    /// Устанавливает интерпретатор и флаг для передачи ему командной строки
+    ///
+    /// Например, `with_shell("bash", "-c")`. Наличие интерпретатора проверяется при выполнении.
+    pub fn with_shell(mut self, program: &str, flag: &str) -> Self {
+        self.shell = Some((program.to_string(), flag.to_string()));
+        self
+    }
+
+    /// Выполняет команду через PowerShell (`pwsh -Command`, если он установлен,
+    /// иначе `powershell -Command`)
+    ///
+    /// Подстановка переменных выполняется до передачи команды интерпретатору.
+    pub fn with_powershell(self) -> Self {
+        self.with_shell(process::powershell_program(), "-Command")
+    }
+
+    /// Возвращает интерпретатор и флаг для передачи ему командной строки
+    fn shell(&self) -> (&str, &str) {
+        if let Some((program, flag)) = &self.shell {
+            return (program, flag);
+        }
+
+        #[cfg(target_family = "unix")]
+        return ("sh", "-c");
+
+        #[cfg(target_family = "windows")]
+        return ("cmd.exe", "/C");
+    }
+
+    /// Устанавливает сохранение стандартного вывода без декодирования
+    ///
+    /// Вывод сохраняется в `CommandResult::stdout_bytes` без замены байтов,
+    /// не являющихся UTF-8 (например, архив, выводимый `tar`), а поле `output`
+    /// остается пустым.
+    pub fn with_binary_output(mut self, binary: bool) -> Self {
+        self.binary_output = binary;
+        self
+    }
+
+    /// Ограничивает размер сохраняемого вывода каждого потока (stdout, stderr)
+    ///
+    /// Вывод сверх ограничения отбрасывается, но процесс выполняется до конца,
+    /// а результат отмечается флагом `truncated`.
+    pub fn with_max_output_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_output_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Устанавливает имена команд, от которых зависит команда (режим DAG)
+    pub fn with_depends_on(mut self, names: &[&str]) -> Self {
+        self.depends_on = names.iter().map(|name| name.to_string()).collect();
+        self
+    }
+
+    /// Определяет настройки изоляции, применимые на текущей платформе
+    fn resolve_sandbox(
+        &self,
+        context: &ExecutionContext<'_>,
+    ) -> Result<Option<&SandboxConfig>, CommandError> {
+        match &self.sandbox {
+            Some(config) if SandboxConfig::is_supported() => Ok(Some(config)),
+            Some(_) if self.sandbox_optional => {
+                if let Some(logger) = context.logger {
+                    logger.warning(&format!(
+                        "Изоляция недоступна, команда '{}' выполняется без нее",
+                        self.name
+                    ));
+                }
+                Ok(None)
+            }
+            Some(_) => Err(SandboxConfig::unsupported_error()),
+            None => Ok(None),
+        }
+    }
+
+    /// Проверяет, считается ли код возврата успешным
+    fn is_success_code(&self, code: Option<i32>) -> bool {
+        code.is_some_and(|code| self.success_codes.contains(&code))
+    }
+
+    /// Возвращает ключ для записи и воспроизведения результатов команды
+    ///
+    /// Ключ учитывает имя, командную строку после подстановки переменных, рабочую
+    /// директорию и действующие переменные окружения команды: окружение цепочки,
+    /// файла `.env` и самой команды (в отсортированном порядке). Окружение
+    /// текущего процесса в ключ не входит. В открытом виде ключ содержит только
+    /// имя и командную строку со скрытыми значениями (как в `resolved_command`),
+    /// остальное входит в него в виде хэша SHA-256, чтобы значения секретных
+    /// переменных и окружения не сохранялись в файле записей.
+    pub(crate) async fn cache_key(
+        &self,
+        context: &ExecutionContext<'_>,
+    ) -> Result<String, CommandError> {
+        let (processed_command, resolved_command) =
+            self.process_variables(&self.command, context).await?;
+        let file_env = self.load_env_file()?;
+
+        let processed_command = if self.windows_env_expansion {
+            self.expand_windows_env(&processed_command, context.env, &file_env)
+        } else {
+            processed_command
+        };
+
+        // Переменные применяются в том же порядке, что и при запуске процесса
+        let mut effective_env: HashMap<&str, &str> = HashMap::new();
+        let env_sources = context
+            .env
+            .into_iter()
+            .flatten()
+            .chain(file_env.iter().map(|(key, value)| (key, value)))
+            .chain(&self.env_vars);
+        for (key, value) in env_sources {
+            effective_env.insert(key, value);
+        }
+
+        let mut env: Vec<String> = effective_env
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect();
+        env.sort();
+
+        let mut hasher = Sha256::new();
+        for part in [
+            processed_command.as_str(),
+            self.working_dir.as_deref().unwrap_or(""),
+            &env.join("\0"),
+        ] {
+            hasher.update(part.as_bytes());
+            hasher.update([0]);
+        }
+        let digest: String = hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+
+        Ok(format!("{}|{}|{}", self.name, resolved_command, digest))
+    }
+
+    /// Выводит команду перед выполнением в стиле `set -x`
+    fn echo_command(command: &str, context: &ExecutionContext<'_>) {
+        let line = format!("+ {}", command);
+
+        match context.logger {
+            Some(logger) => logger.info(&line),
+            None => eprintln!("{}", line),
+        }
+    }
+
     /// Интерактивный ввод значения переменной
     async fn prompt_for_variable(var_name: &str) -> Result<String, CommandError> {
         let mut stdout = io::stdout();
         stdout
             .write_all(format!("Введите значение для {}: ", var_name).as_bytes())
             .await
-            .map_err(|e| CommandError::IoError(e))?;
-        stdout.flush().await.map_err(|e| CommandError::IoError(e))?;
+            .map_err(CommandError::IoError)?;
+        stdout.flush().await.map_err(CommandError::IoError)?;
 
         let mut buffer = String::new();
         stdio::stdin()
             .lock()
             .read_line(&mut buffer)
-            .map_err(|e| CommandError::IoError(e))?;
+            .map_err(CommandError::IoError)?;
+
+        Ok(buffer.trim().to_string())
+    }
+
+    /// Интерактивный ввод секретного значения без отображения вводимых символов
+    async fn prompt_for_secret(var_name: &str) -> Result<String, CommandError> {
+        let mut stdout = io::stdout();
+        stdout
+            .write_all(format!("Введите значение для {}: ", var_name).as_bytes())
+            .await
+            .map_err(CommandError::IoError)?;
+        stdout.flush().await.map_err(CommandError::IoError)?;
+
+        let buffer = terminal::read_hidden_line().map_err(CommandError::IoError)?;
 
         Ok(buffer.trim().to_string())
     }
@@ -139,7 +820,12 @@ impl ShellCommand {
             CommandError::ExecutionError(format!("Не удалось прочитать файл с переменными: {}", e))
         })?;
 
-        let json: Value = serde_json::from_str(&contents).map_err(|e| {
+        Self::parse_variables(&contents)
+    }
+
+    /// Разбирает переменные из JSON-объекта
+    fn parse_variables(contents: &str) -> Result<HashMap<String, String>, CommandError> {
+        let json: Value = serde_json::from_str(contents).map_err(|e| {
             CommandError::ExecutionError(format!("Не удалось разобрать JSON: {}", e))
         })?;
 
@@ -157,10 +843,100 @@ impl ShellCommand {
         Ok(vars)
     }
 
+    /// Подставляет переменные в командную строку без интерактивного ввода
+    ///
+    /// Переменные `{#var}` и `{var}` берутся из файла переменных, `{$var}` - из окружения.
+    /// Переменные, значения которых неизвестны, остаются без изменений.
+    fn preview_variables(&self, cmd: &str) -> String {
+        let file_vars = self
+            .variables_file
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|contents| Self::parse_variables(&contents).ok())
+            .unwrap_or_default();
+
+        let processed_cmd = FILE_VAR_PATTERN.replace_all(cmd, |cap: &regex::Captures| {
+            file_vars
+                .get(&cap[1])
+                .cloned()
+                .unwrap_or_else(|| cap[0].to_string())
+        });
+
+        let processed_cmd = ENV_VAR_PATTERN.replace_all(&processed_cmd, |cap: &regex::Captures| {
+            env::var(&cap[1]).unwrap_or_else(|_| cap[0].to_string())
+        });
+
+        INTERACTIVE_VAR_PATTERN
+            .replace_all(&processed_cmd, |cap: &regex::Captures| {
+                file_vars
+                    .get(&cap[1])
+                    .cloned()
+                    .unwrap_or_else(|| cap[0].to_string())
+            })
+            .into_owned()
+    }
+
     /// Заменяет переменные в командной строке
-    async fn process_variables(&self, cmd: &str) -> Result<String, CommandError> {
-        let mut processed_cmd = cmd.to_string();
+    ///
+    /// Возвращает командную строку для выполнения и ее вариант для отображения,
+    /// в котором значения, введенные интерактивно, скрыты при включенном
+    /// `redact_variables`. Значения секретных переменных `{!var}` скрываются всегда.
+    ///
+    /// Каждая переменная определяется не более одного раза: повторные вхождения
+    /// (в том числе в другой форме подстановки) получают уже определенное значение.
+    /// При наличии общего кэша в контексте значения разделяются между командами.
+    ///
+    /// Подстановки ищутся только в исходной командной строке и заменяются за один
+    /// проход, поэтому фигурные скобки в подставленных значениях (выводе команд,
+    /// захваченных переменных) не разбираются как подстановки.
+    async fn process_variables(
+        &self,
+        cmd: &str,
+        context: &ExecutionContext<'_>,
+    ) -> Result<(String, String), CommandError> {
         let mut file_vars = HashMap::new();
+        // Значения, уже определенные другими командами цепочки
+        let mut resolved = context
+            .variable_cache
+            .map(VariableCache::snapshot)
+            .unwrap_or_default();
+        // Значения подстановок шаблона по их тексту (например, `{#name}`)
+        let mut placeholders: HashMap<String, ResolvedVariable> = HashMap::new();
+
+        // Подставляем вывод прямой команды при откате
+        if let Some(result) = context.forward_result {
+            let output = result.output.trim().to_string();
+            placeholders.insert(
+                OUTPUT_PLACEHOLDER.to_string(),
+                ResolvedVariable::plain(output),
+            );
+        }
+
+        // Явно заданные значения имеют приоритет над остальными источниками
+        for cap in VAR_PATTERN.captures_iter(cmd) {
+            let var_name = cap[1].strip_prefix(['#', '$', '!']).unwrap_or(&cap[1]);
+            if let Some(value) = context.override_value(var_name) {
+                let variable = if cap[1].starts_with('!') {
+                    ResolvedVariable::hidden(value.to_string())
+                } else {
+                    ResolvedVariable::plain(value.to_string())
+                };
+                placeholders.insert(cap[0].to_string(), variable);
+            }
+        }
+
+        // Подставляем переменные, захваченные из вывода ранее выполненных команд
+        for cap in VAR_PATTERN.captures_iter(cmd) {
+            if placeholders.contains_key(&cap[0]) {
+                continue;
+            }
+            if let Some(value) = context.variable(&cap[1]) {
+                placeholders.insert(
+                    cap[0].to_string(),
+                    ResolvedVariable::plain(value.to_string()),
+                );
+            }
+        }
 
         // Загружаем переменные из файла, если указан
         if let Some(file_path) = &self.variables_file {
@@ -168,56 +944,439 @@ impl ShellCommand {
         }
 
         // Обрабатываем переменные из файла {#var}
-        for cap in FILE_VAR_PATTERN.captures_iter(&cmd.to_string()) {
+        for cap in FILE_VAR_PATTERN.captures_iter(cmd) {
             let var_name = &cap[1];
-            if let Some(_) = &self.variables_file {
-                if let Some(value) = file_vars.get(var_name) {
-                    processed_cmd = processed_cmd.replace(&cap[0], value);
-                } else {
-                    // Если переменной нет в файле, запрашиваем интерактивно
-                    let value = Self::prompt_for_variable(var_name).await?;
-                    processed_cmd = processed_cmd.replace(&cap[0], &value);
-                }
-            } else {
-                // Файл не указан, запрашиваем интерактивно
-                let value = Self::prompt_for_variable(var_name).await?;
-                processed_cmd = processed_cmd.replace(&cap[0], &value);
+            if placeholders.contains_key(&cap[0]) {
+                continue;
             }
+            if !resolved.contains_key(var_name) {
+                let variable = match file_vars.get(var_name) {
+                    Some(value) => ResolvedVariable::plain(value.clone()),
+                    // Если переменной нет в файле (или файл не указан), запрашиваем интерактивно
+                    None => self.prompted(Self::prompt_for_variable(var_name).await?),
+                };
+                resolved.insert(var_name.to_string(), variable);
+            }
+            placeholders.insert(cap[0].to_string(), resolved[var_name].clone());
         }
 
         // Обрабатываем переменные окружения {$var}
-        for cap in ENV_VAR_PATTERN.captures_iter(&processed_cmd.clone()) {
+        for cap in ENV_VAR_PATTERN.captures_iter(cmd) {
             let var_name = &cap[1];
-            if let Ok(value) = env::var(var_name) {
-                processed_cmd = processed_cmd.replace(&cap[0], &value);
-            } else {
-                // Если переменной нет в окружении, запрашиваем интерактивно
-                let value = Self::prompt_for_variable(var_name).await?;
-                processed_cmd = processed_cmd.replace(&cap[0], &value);
+            if placeholders.contains_key(&cap[0]) {
+                continue;
+            }
+            if !resolved.contains_key(var_name) {
+                let variable = match env::var(var_name) {
+                    Ok(value) => ResolvedVariable::plain(value),
+                    // Если переменной нет в окружении, запрашиваем интерактивно
+                    Err(_) => self.prompted(Self::prompt_for_variable(var_name).await?),
+                };
+                resolved.insert(var_name.to_string(), variable);
+            }
+            placeholders.insert(cap[0].to_string(), resolved[var_name].clone());
+        }
+
+        // Обрабатываем секретные переменные {!var}: значение вводится без эха
+        // и всегда скрывается в отображаемой версии
+        for cap in SECRET_VAR_PATTERN.captures_iter(cmd) {
+            let var_name = &cap[1];
+            if placeholders.contains_key(&cap[0]) {
+                continue;
             }
+            let value = match resolved.get(var_name) {
+                Some(variable) => variable.value.clone(),
+                None => Self::prompt_for_secret(var_name).await?,
+            };
+            resolved.insert(var_name.to_string(), ResolvedVariable::hidden(value));
+            placeholders.insert(cap[0].to_string(), resolved[var_name].clone());
         }
 
         // Обрабатываем интерактивные переменные {var}
-        for cap in INTERACTIVE_VAR_PATTERN.captures_iter(&processed_cmd.clone()) {
+        for cap in INTERACTIVE_VAR_PATTERN.captures_iter(cmd) {
             let var_name = &cap[1];
-            let value = Self::prompt_for_variable(var_name).await?;
-            processed_cmd = processed_cmd.replace(&cap[0], &value);
+            if placeholders.contains_key(&cap[0]) {
+                continue;
+            }
+            if !resolved.contains_key(var_name) {
+                let variable = self.prompted(Self::prompt_for_variable(var_name).await?);
+                resolved.insert(var_name.to_string(), variable);
+            }
+            placeholders.insert(cap[0].to_string(), resolved[var_name].clone());
+        }
+
+        if let Some(cache) = context.variable_cache {
+            cache.extend(resolved);
+        }
+
+        // Заменяет подстановки шаблона за один проход
+        let replace = |hide: bool| {
+            VAR_PATTERN
+                .replace_all(cmd, |cap: &regex::Captures| {
+                    match placeholders.get(&cap[0]) {
+                        Some(variable) if hide && variable.hidden => REDACTED_VALUE.to_string(),
+                        Some(variable) => variable.value.clone(),
+                        None => cap[0].to_string(),
+                    }
+                })
+                .into_owned()
+        };
+
+        Ok((replace(false), replace(true)))
+    }
+
+    /// Оборачивает интерактивно введенное значение (скрывается при `redact_variables`)
+    fn prompted(&self, value: String) -> ResolvedVariable {
+        ResolvedVariable {
+            value,
+            hidden: self.redact_variables,
+        }
+    }
+
+    /// Проверяет рабочую директорию перед запуском, создавая ее при необходимости
+    fn prepare_working_dir(&self) -> Result<(), CommandError> {
+        let dir = match &self.working_dir {
+            Some(dir) => dir,
+            None => return Ok(()),
+        };
+
+        let path = Path::new(dir);
+
+        if !path.exists() && self.create_working_dir {
+            std::fs::create_dir_all(path).map_err(|err| {
+                CommandError::ExecutionError(format!(
+                    "Не удалось создать рабочую директорию '{}': {}",
+                    dir, err
+                ))
+            })?;
+        }
+
+        if !path.exists() {
+            return Err(CommandError::ExecutionError(format!(
+                "Рабочая директория '{}' не существует",
+                dir
+            )));
+        }
+
+        if !path.is_dir() {
+            return Err(CommandError::ExecutionError(format!(
+                "Рабочая директория '{}' не является директорией",
+                dir
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Подставляет переменные окружения вида `%VAR%` с тем же приоритетом, что и при запуске
+    fn expand_windows_env(
+        &self,
+        command: &str,
+        chain_env: Option<&HashMap<String, String>>,
+        file_env: &[(String, String)],
+    ) -> String {
+        // Имена переменных окружения Windows не зависят от регистра
+        let same_name = |a: &str, b: &str| {
+            if cfg!(windows) {
+                a.eq_ignore_ascii_case(b)
+            } else {
+                a == b
+            }
+        };
+
+        // Источники проверяются по убыванию приоритета; из нескольких имен,
+        // подходящих без учета регистра, берется наименьшее, чтобы результат
+        // не зависел от порядка обхода `HashMap`
+        let find = |name: &str, matches: fn(&str, &str) -> bool| {
+            let from_map = |map: &HashMap<String, String>| {
+                map.iter()
+                    .filter(|(key, _)| matches(key, name))
+                    .min_by(|(a, _), (b, _)| a.cmp(b))
+                    .map(|(_, value)| value.clone())
+            };
+
+            from_map(&self.env_vars)
+                .or_else(|| {
+                    file_env
+                        .iter()
+                        .rev()
+                        .find(|(key, _)| matches(key, name))
+                        .map(|(_, value)| value.clone())
+                })
+                .or_else(|| chain_env.and_then(from_map))
+        };
+
+        // Сначала ищется точное совпадение имени, затем совпадение без учета регистра
+        let lookup = |name: &str| {
+            find(name, |a, b| a == b)
+                .or_else(|| {
+                    cfg!(windows)
+                        .then(|| find(name, |a, b| a.eq_ignore_ascii_case(b)))
+                        .flatten()
+                })
+                .or_else(|| {
+                    let removed =
+                        self.clean_env || self.env_remove.iter().any(|key| same_name(key, name));
+                    if removed {
+                        None
+                    } else {
+                        env::var(name).ok()
+                    }
+                })
+        };
+
+        WINDOWS_ENV_PATTERN
+            .replace_all(command, |caps: &regex::Captures| {
+                lookup(&caps[1]).unwrap_or_else(|| caps[0].to_string())
+            })
+            .into_owned()
+    }
+
+    /// Загружает переменные окружения из файла `.env`, если он задан
+    fn load_env_file(&self) -> Result<Vec<(String, String)>, CommandError> {
+        let path = match &self.env_file {
+            Some(path) => path,
+            None => return Ok(Vec::new()),
+        };
+
+        let read_error = |err: dotenv::Error| {
+            CommandError::ExecutionError(format!(
+                "Не удалось прочитать файл окружения '{}': {}",
+                path, err
+            ))
+        };
+
+        // Итератор разбирает файл без изменения окружения текущего процесса
+        #[allow(deprecated)]
+        let entries = dotenv::from_path_iter(path).map_err(read_error)?;
+
+        entries.map(|entry| entry.map_err(read_error)).collect()
+    }
+
+    /// Создает процесс оболочки для выполнения командной строки
+    fn build_process(
+        &self,
+        processed_command: &str,
+        sandbox: Option<&SandboxConfig>,
+        chain_env: Option<&HashMap<String, String>>,
+        file_env: &[(String, String)],
+    ) -> TokioCommand {
+        let (program, flag) = self.shell();
+        let args = [flag, processed_command];
+
+        let mut cmd = match sandbox {
+            Some(config) => {
+                let (sandbox_program, sandbox_args) = config.wrap(program, &args);
+                let mut cmd = TokioCommand::new(sandbox_program);
+                cmd.args(sandbox_args);
+                cmd
+            }
+            None => {
+                let mut cmd = TokioCommand::new(program);
+                cmd.args(args);
+                cmd
+            }
+        };
+
+        // Устанавливаем рабочую директорию, если указана
+        if let Some(dir) = &self.working_dir {
+            cmd.current_dir(dir);
         }
 
-        Ok(processed_cmd)
+        // Очищаем наследуемое окружение, если требуется
+        if self.clean_env {
+            cmd.env_clear();
+        }
+
+        for key in &self.env_remove {
+            cmd.env_remove(key);
+        }
+
+        // Переменные цепочки переопределяются переменными команды
+        for (key, value) in chain_env.into_iter().flatten() {
+            cmd.env(key, value);
+        }
+
+        // Переменные из файла окружения переопределяются явно заданными
+        for (key, value) in file_env {
+            cmd.env(key, value);
+        }
+
+        // Устанавливаем переменные окружения
+        for (key, value) in &self.env_vars {
+            cmd.env(key, value);
+        }
+
+        // Устанавливаем umask дочернего процесса перед запуском
+        #[cfg(target_family = "unix")]
+        if let Some(mask) = self.umask {
+            // Безопасно: umask является async-signal-safe и не выделяет память
+            unsafe {
+                cmd.pre_exec(move || {
+                    libc::umask(mask as libc::mode_t);
+                    Ok(())
+                });
+            }
+        }
+
+        // Ввод передается только при наличии данных
+        if self.stdin.is_some() {
+            cmd.stdin(Stdio::piped());
+        } else {
+            cmd.stdin(Stdio::null());
+        }
+
+        // Вывод читается построчно, а процесс завершается при прерывании выполнения
+        cmd.stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+
+        cmd
     }
 
-    /// Выполняет токио команду с таймаутом
-    async fn execute_with_timeout(&self) -> Result<CommandResult, CommandError> {
+    /// Создает пустой результат команды с ее идентификатором и метками
+    fn new_result(&self) -> CommandResult {
+        let mut result = CommandResult::new(&self.name);
+
+        if let Some(id) = &self.id {
+            result.id = id.clone();
+        }
+
+        result.tags = self.tags.clone();
+        result
+    }
+
+    /// Формирует результат выполнения по выводу и коду возврата
+    fn build_result(
+        &self,
+        result: CommandResult,
+        stdout: String,
+        stderr: String,
+        exit_code: Option<i32>,
+    ) -> CommandResult {
+        // Пользовательский обработчик вывода заменяет проверку по коду возврата
+        if let Some(parser) = &self.output_parser {
+            let parsed = parser.parse(&stdout, exit_code);
+            let result = result.with_data(parsed.data);
+
+            return if parsed.success {
+                result.success(stdout).with_exit_code(exit_code)
+            } else {
+                let error_msg = parsed
+                    .message
+                    .unwrap_or_else(|| "Обработчик вывода отклонил результат".to_string());
+                result.failure(error_msg, exit_code)
+            };
+        }
+
+        if self.is_success_code(exit_code) {
+            result.success(stdout).with_exit_code(exit_code)
+        } else {
+            let error_msg = if stderr.is_empty() {
+                format!(
+                    "Команда завершилась с ошибкой: код {}",
+                    exit_code.unwrap_or(-1)
+                )
+            } else {
+                stderr
+            };
+
+            result.failure(error_msg, exit_code)
+        }
+    }
+
+    /// Отмечает успешный результат как неудачный, если вывод не соответствует ожиданиям
+    fn check_expected_output(&self, result: CommandResult) -> CommandResult {
+        if !result.success {
+            return result;
+        }
+
+        if let Some(expected) = &self.expect_output_contains {
+            if !result.output.contains(expected.as_str()) {
+                let message = format!(
+                    "Вывод команды '{}' не содержит ожидаемую строку '{}'",
+                    self.name, expected
+                );
+                let exit_code = result.exit_code;
+                return result.failure(message, exit_code);
+            }
+        }
+
+        if let Some(pattern) = &self.expect_output_matches {
+            if !pattern.is_match(&result.output) {
+                let message = format!(
+                    "Вывод команды '{}' не совпадает с шаблоном '{}'",
+                    self.name, pattern
+                );
+                let exit_code = result.exit_code;
+                return result.failure(message, exit_code);
+            }
+        }
+
+        result
+    }
+
+    /// Преобразует ошибку запуска процесса: отсутствие программы и недостаток прав
+    /// отличаются от прочих ошибок ввода/вывода
+    fn spawn_error(cmd: &TokioCommand, err: std::io::Error) -> CommandError {
+        match err.kind() {
+            std::io::ErrorKind::NotFound | std::io::ErrorKind::PermissionDenied => {
+                CommandError::SpawnError(format!(
+                    "не удалось запустить '{}': {}",
+                    cmd.as_std().get_program().to_string_lossy(),
+                    err
+                ))
+            }
+            _ => CommandError::IoError(err),
+        }
+    }
+
+    /// Выполняет токио команду с таймаутом, передавая строки вывода обработчику
+    async fn execute_with_timeout<F>(
+        &self,
+        context: &ExecutionContext<'_>,
+        mut on_line: F,
+    ) -> Result<CommandResult, CommandError>
+    where
+        F: FnMut(OutputStream, &str),
+    {
+        let invoked = std::time::Instant::now();
+
+        // Результат создается до подстановки переменных, чтобы длительность
+        // выполнения включала время до запуска процесса
+        let result = self.new_result();
+
         // Обрабатываем переменные в команде
-        let processed_command = self.process_variables(&self.command).await?;
+        let (processed_command, resolved_command) =
+            self.process_variables(&self.command, context).await?;
+
+        // Логгер, которому передаются строки вывода при выводе команды
+        let output_logger = if self.echo || context.echo {
+            Self::echo_command(&resolved_command, context);
+            context.logger
+        } else {
+            None
+        };
+
+        // В режиме пробного запуска процесс не создается
+        if context.dry_run {
+            let message = format!("[dry-run] {}", resolved_command);
+
+            if let Some(logger) = context.logger {
+                logger.info(&message);
+            }
+
+            let mut result = self.new_result().success(message);
+            result.resolved_command = resolved_command;
+            return Ok(result);
+        }
 
         let args = match split(&processed_command) {
             Some(args) => args,
             None => {
                 return Err(CommandError::ExecutionError(format!(
                     "Не удалось разобрать команду: {}",
-                    processed_command
+                    resolved_command
                 )))
             }
         };
@@ -226,62 +1385,400 @@ impl ShellCommand {
             return Err(CommandError::ExecutionError("Пустая команда".to_string()));
         }
 
-        let result = CommandResult::new(&self.name);
+        // Проверяем наличие заданного интерпретатора
+        if let Some((program, _)) = &self.shell {
+            if process::find_program(program).is_none() {
+                return Err(CommandError::SpawnError(format!(
+                    "Интерпретатор '{}' не найден",
+                    program
+                )));
+            }
+        }
 
-        #[cfg(target_family = "unix")]
-        let program = "sh";
-        #[cfg(target_family = "unix")]
-        let args = ["-c", &processed_command];
+        self.prepare_working_dir()?;
 
-        #[cfg(target_family = "windows")]
-        let program = "cmd.exe";
-        #[cfg(target_family = "windows")]
-        let args = ["/C", &processed_command];
+        let sandbox = self.resolve_sandbox(context)?;
+        let file_env = self.load_env_file()?;
 
-        let mut cmd = TokioCommand::new(program);
-        cmd.args(&args);
+        let processed_command = if self.windows_env_expansion {
+            self.expand_windows_env(&processed_command, context.env, &file_env)
+        } else {
+            processed_command
+        };
 
-        // Устанавливаем рабочую директорию, если указана
-        if let Some(dir) = &self.working_dir {
-            cmd.current_dir(dir);
-        }
+        let mut cmd = self.build_process(&processed_command, sandbox, context.env, &file_env);
 
-        // Устанавливаем переменные окружения
-        for (key, value) in &self.env_vars {
-            cmd.env(key, value);
+        // Вывод собирается вне выполняемого future, чтобы сохранить его при таймауте
+        let mut captured = CapturedOutput::default();
+        let started = std::time::Instant::now();
+
+        // Запускаем команду
+        let mut child = cmd.spawn().map_err(|err| Self::spawn_error(&cmd, err))?;
+        let spawn_latency_ms = invoked.elapsed().as_millis() as u64;
+
+        if let Some(pid) = child.id() {
+            context.record_pid(pid);
         }
 
-        // Запускаем команду и получаем результат
-        let exec_future = cmd.output();
+        // Собираем вывод
+        let exec_future = async {
+            // Ввод записывается одновременно с чтением вывода, чтобы избежать взаимной блокировки
+            let stdin_writer = process::write_stdin(child.stdin.take(), self.stdin.as_deref());
+            let (written, read) = tokio::join!(
+                stdin_writer,
+                process::capture_output(
+                    &mut child,
+                    &mut captured,
+                    self.max_output_bytes,
+                    |stream, line| {
+                        if let Some(logger) = output_logger {
+                            logger.log_output(stream, line);
+                        }
+                        on_line(stream, line)
+                    }
+                )
+            );
+            written?;
+            read?;
+
+            // Ресурсы считываются до освобождения процесса ожиданием его кода возврата
+            let usage = process::wait_resource_usage(child.id()).await;
+            let status = child.wait().await?;
+            Ok::<_, std::io::Error>((status, usage))
+        };
 
         // Применяем таймаут, если установлен
-        let output = if let Some(timeout_secs) = self.timeout_seconds {
-            match tokio::time::timeout(std::time::Duration::from_secs(timeout_secs), exec_future)
-                .await
-            {
+        // Собственный таймаут команды имеет приоритет над таймаутом по умолчанию цепочки
+        let timeout = self.timeout().or(context.default_timeout);
+
+        let (status, usage) = if let Some(timeout) = timeout {
+            let outcome = tokio::time::timeout(timeout, exec_future).await;
+
+            match outcome {
                 Ok(res) => res?,
-                Err(_) => return Err(CommandError::TimeoutError),
+                Err(_) => {
+                    // Вывод, полученный до таймаута, сохраняется
+                    process::terminate(&mut child, self.kill_grace).await;
+
+                    let message =
+                        format!("команда '{}' не завершилась за {:?}", self.name, timeout);
+                    let stderr = String::from_utf8_lossy(&captured.stderr);
+                    let error = if stderr.is_empty() {
+                        message
+                    } else {
+                        format!("{}\n{}", message, stderr.trim_end())
+                    };
+
+                    let mut result = result.timeout(error);
+                    if self.binary_output {
+                        result.stdout_bytes = std::mem::take(&mut captured.stdout);
+                    } else {
+                        result.output = String::from_utf8_lossy(&captured.stdout).to_string();
+                    }
+                    result.spawn_latency_ms = spawn_latency_ms;
+                    result.exec_ms = started.elapsed().as_millis() as u64;
+                    result.truncated = captured.truncated;
+                    result.resolved_command = resolved_command;
+                    return Ok(result);
+                }
             }
         } else {
             exec_future.await?
         };
+        let exec_time = started.elapsed();
 
-        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-
-        if output.status.success() {
-            Ok(result.success(stdout))
+        // Двоичный вывод сохраняется как есть, без декодирования в строку
+        let (stdout, stdout_bytes) = if self.binary_output {
+            (String::new(), std::mem::take(&mut captured.stdout))
         } else {
-            let error_msg = if stderr.is_empty() {
+            (
+                String::from_utf8_lossy(&captured.stdout).to_string(),
+                Vec::new(),
+            )
+        };
+        let stderr = String::from_utf8_lossy(&captured.stderr).to_string();
+
+        let result = self.build_result(result, stdout, stderr, status.code());
+        let mut result = self.check_expected_output(result);
+        result.stdout_bytes = stdout_bytes;
+        result.spawn_latency_ms = spawn_latency_ms;
+        result.exec_ms = exec_time.as_millis() as u64;
+        result.truncated = captured.truncated;
+        result.resolved_command = resolved_command;
+
+        if let Some(usage) = usage {
+            result.cpu_time_ms = Some(usage.cpu_time_ms);
+            result.max_rss_kb = Some(usage.max_rss_kb);
+        }
+
+        // Сохраняем вывод успешной команды в переменную
+        if let (true, Some(name)) = (result.success, &self.capture_as) {
+            let value = result.output.trim().to_string();
+            result.captured.insert(name.clone(), value);
+        }
+
+        Ok(result)
+    }
+
+    /// Выполняет команду, повторяя ее до выполнения условия ожидания (если задано)
+    async fn execute_with_wait<F>(
+        &self,
+        context: &ExecutionContext<'_>,
+        mut on_line: F,
+    ) -> Result<CommandResult, CommandError>
+    where
+        F: FnMut(OutputStream, &str),
+    {
+        let condition = match &self.wait_until {
+            Some(condition) => condition,
+            None => return self.execute_with_timeout(context, on_line).await,
+        };
+
+        // Значения переменных определяются один раз на все попытки
+        let cache = VariableCache::new();
+        let context = match context.variable_cache {
+            Some(_) => *context,
+            None => context.with_variable_cache(&cache),
+        };
+
+        let mut waited = Duration::ZERO;
+        let mut attempt = 1;
+
+        loop {
+            let mut result = self.execute_with_timeout(&context, &mut on_line).await?;
+            result.retry_wait_ms = waited.as_millis() as u64;
+
+            if result.success && condition.pattern.is_match(&result.output) {
+                return Ok(result);
+            }
+
+            if attempt >= condition.max_attempts {
+                let message = format!(
+                    "вывод команды '{}' не совпал с шаблоном '{}' за {} попыток",
+                    self.name, condition.pattern, condition.max_attempts
+                );
+                return Ok(result.timeout(message));
+            }
+
+            if let Some(logger) = context.logger {
+                logger.debug(&format!(
+                    "Команда '{}': условие ожидания не выполнено (попытка {} из {})",
+                    self.name, attempt, condition.max_attempts
+                ));
+            }
+
+            tokio::time::sleep(condition.interval).await;
+            waited += condition.interval;
+            attempt += 1;
+        }
+    }
+
+    /// Выполняет команду вне цепочки, логируя ее запуск, командную строку и результат
+    ///
+    /// Логгер также передается в контекст выполнения (вывод команды перед
+    /// выполнением, периодическое логирование и т.п.).
+    pub async fn execute_with_logger(
+        &self,
+        logger: &dyn Logger,
+    ) -> Result<CommandResult, CommandError> {
+        let log_context = LogContext::new().with_extra(serde_json::json!({ "command": self.name }));
+        logger.log_with_context(
+            LogLevel::Info,
+            &format!("Выполнение команды '{}'", self.name),
+            &log_context,
+        );
+
+        let context = ExecutionContext::new().with_logger(logger);
+        let result = self.execute_with_context(&context).await;
+
+        let (level, message) = match &result {
+            Ok(cmd_result) => {
+                logger.log_with_context(
+                    LogLevel::Debug,
+                    &format!(
+                        "Команда '{}' выполнена как: {}",
+                        self.name, cmd_result.resolved_command
+                    ),
+                    &log_context,
+                );
+
+                if cmd_result.success {
+                    (
+                        LogLevel::Info,
+                        format!("Команда '{}' успешно выполнена", self.name),
+                    )
+                } else {
+                    (
+                        LogLevel::Error,
+                        format!(
+                            "Ошибка выполнения команды '{}': {}",
+                            self.name,
+                            cmd_result
+                                .error
+                                .as_deref()
+                                .unwrap_or("<неизвестная ошибка>")
+                        ),
+                    )
+                }
+            }
+            Err(err) => (
+                LogLevel::Error,
                 format!(
-                    "Команда завершилась с ошибкой: код {}",
-                    output.status.code().unwrap_or(-1)
-                )
-            } else {
-                stderr
-            };
+                    "Критическая ошибка выполнения команды '{}': {}",
+                    self.name, err
+                ),
+            ),
+        };
+
+        logger.log_with_context(level, &message, &log_context);
+        result
+    }
 
-            Ok(result.failure(error_msg, output.status.code()))
+    /// Выполняет команду синхронно, без внешней среды выполнения tokio
+    ///
+    /// Создает собственную однопоточную среду выполнения. При вызове изнутри
+    /// среды выполнения tokio возвращает ошибку (используйте `execute`).
+    pub fn execute_blocking(&self) -> Result<CommandResult, CommandError> {
+        runtime::block_on(self.execute())
+    }
+
+    /// Выполняет команду, вызывая обработчик для каждой строки вывода по мере поступления
+    ///
+    /// Строки stdout и stderr передаются без завершающего перевода строки вместе
+    /// с потоком, из которого они получены. Итоговый результат содержит весь собранный вывод.
+    pub async fn execute_streaming<F>(&self, on_line: F) -> Result<CommandResult, CommandError>
+    where
+        F: FnMut(OutputStream, &str),
+    {
+        self.execute_with_wait(&ExecutionContext::default(), on_line)
+            .await
+    }
+}
+
+impl ShellCommand {
+    /// Создает вспомогательную команду (отката или обработки ошибки) с настройками этой команды
+    fn derived_command(&self, suffix: &str, command: &str) -> Self {
+        let mut derived = Self::new(&format!("{}_{}", self.name, suffix), command);
+
+        if let Some(dir) = &self.working_dir {
+            derived.working_dir = Some(dir.clone());
+        }
+
+        derived.create_working_dir = self.create_working_dir;
+        derived.env_vars = self.env_vars.clone();
+        derived.clean_env = self.clean_env;
+        derived.env_remove = self.env_remove.clone();
+        derived.env_file = self.env_file.clone();
+        derived.mode = self.mode;
+        derived.echo = self.echo;
+        derived.redact_variables = self.redact_variables;
+        derived.umask = self.umask;
+        derived.success_codes = self.success_codes.clone();
+        derived.sandbox = self.sandbox.clone();
+        derived.sandbox_optional = self.sandbox_optional;
+        derived.shell = self.shell.clone();
+        derived.binary_output = self.binary_output;
+        derived.kill_grace = self.kill_grace;
+        derived.windows_env_expansion = self.windows_env_expansion;
+        derived.tags = self.tags.clone();
+        derived.id = self.id.as_ref().map(|id| format!("{}_{}", id, suffix));
+
+        // Передаем файл с переменными во вспомогательную команду
+        if let Some(vars_file) = &self.variables_file {
+            derived.variables_file = Some(vars_file.clone());
+        }
+
+        derived
+    }
+
+    /// Выполняет команду, периодически логируя ход выполнения (если задан интервал)
+    async fn execute_with_heartbeat(
+        &self,
+        context: &ExecutionContext<'_>,
+    ) -> Result<CommandResult, CommandError> {
+        let execution = self.execute_with_wait(context, |_, _| {});
+
+        let (interval, logger) = match (self.heartbeat, context.logger) {
+            (Some(interval), Some(logger)) => (interval, logger),
+            _ => return execution.await,
+        };
+
+        tokio::pin!(execution);
+        let started = std::time::Instant::now();
+        let mut ticker = tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+
+        // Как и остальные сообщения цепочки, строки содержат имя цепочки (если она есть)
+        let mut extra = serde_json::json!({ "command": self.name });
+        if let Some(chain) = context.chain_name {
+            extra["chain"] = chain.into();
+        }
+        let log_context = LogContext::new().with_extra(extra);
+
+        loop {
+            tokio::select! {
+                result = &mut execution => return result,
+                _ = ticker.tick() => {
+                    logger.log_with_context(
+                        LogLevel::Info,
+                        &format!(
+                            "Команда '{}' выполняется (прошло {} с)",
+                            self.name,
+                            started.elapsed().as_secs()
+                        ),
+                        &log_context,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Выполняет команду `on_error`, если команда завершилась с ошибкой
+    async fn run_on_error(
+        &self,
+        context: &ExecutionContext<'_>,
+        outcome: &Result<CommandResult, CommandError>,
+    ) {
+        let on_error = match &self.on_error {
+            Some(on_error) if !context.dry_run => on_error,
+            _ => return,
+        };
+
+        let failed = match outcome {
+            Ok(result) => !result.success && !result.is_skipped(),
+            Err(_) => true,
+        };
+
+        if !failed {
+            return;
+        }
+
+        let context = match outcome {
+            Ok(result) => context.with_forward_result(result),
+            Err(_) => *context,
+        };
+
+        let handler = self.derived_command("on_error", on_error);
+        let message = match handler.execute_with_wait(&context, |_, _| {}).await {
+            Ok(result) if result.success => None,
+            Ok(result) => Some(
+                result
+                    .error
+                    .unwrap_or_else(|| "Неизвестная ошибка".to_string()),
+            ),
+            Err(err) => Some(err.to_string()),
+        };
+
+        if let Some(logger) = context.logger {
+            match message {
+                None => logger.info(&format!(
+                    "Обработчик ошибки команды '{}' выполнен",
+                    self.name
+                )),
+                Some(message) => logger.error(&format!(
+                    "Ошибка обработчика ошибки команды '{}': {}",
+                    self.name, message
+                )),
+            }
         }
     }
 }
@@ -289,10 +1786,28 @@ impl ShellCommand {
 #[async_trait]
 impl CommandExecution for ShellCommand {
     async fn execute(&self) -> Result<CommandResult, CommandError> {
-        self.execute_with_timeout().await
+        self.execute_with_context(&ExecutionContext::default())
+            .await
+    }
+
+    async fn execute_with_context(
+        &self,
+        context: &ExecutionContext<'_>,
+    ) -> Result<CommandResult, CommandError> {
+        let outcome = self.execute_with_heartbeat(context).await;
+        self.run_on_error(context, &outcome).await;
+        outcome
     }
 
     async fn rollback(&self) -> Result<CommandResult, CommandError> {
+        self.rollback_with_context(&ExecutionContext::default())
+            .await
+    }
+
+    async fn rollback_with_context(
+        &self,
+        context: &ExecutionContext<'_>,
+    ) -> Result<CommandResult, CommandError> {
         if !self.supports_rollback {
             return Err(CommandError::RollbackError(
                 "Команда не поддерживает откат".to_string(),
@@ -308,21 +1823,54 @@ impl CommandExecution for ShellCommand {
             }
         };
 
-        let mut rollback = Self::new(&format!("{}_rollback", self.name), rollback_cmd);
-
-        if let Some(dir) = &self.working_dir {
-            rollback.working_dir = Some(dir.clone());
+        // Вывод прямой команды известен только при откате из цепочки
+        if rollback_cmd.contains(OUTPUT_PLACEHOLDER) && context.forward_result.is_none() {
+            return Err(CommandError::RollbackError(format!(
+                "Вывод команды '{}' для подстановки {} недоступен",
+                self.name, OUTPUT_PLACEHOLDER
+            )));
         }
 
-        rollback.env_vars = self.env_vars.clone();
-        rollback.mode = self.mode;
+        let rollback = self.derived_command("rollback", rollback_cmd);
 
-        // Передаем файл с переменными в команду отката
-        if let Some(vars_file) = &self.variables_file {
-            rollback.variables_file = Some(vars_file.clone());
+        if self.rollback_retries == 0 {
+            return rollback.execute_with_context(context).await;
         }
 
-        rollback.execute().await
+        // Значения переменных определяются один раз на все попытки
+        let cache = VariableCache::new();
+        let context = match context.variable_cache {
+            Some(_) => *context,
+            None => context.with_variable_cache(&cache),
+        };
+
+        let mut attempt = 0;
+
+        loop {
+            let outcome = rollback.execute_with_context(&context).await;
+
+            let failed = match &outcome {
+                Ok(result) => !result.success,
+                Err(_) => true,
+            };
+
+            if !failed || attempt >= self.rollback_retries {
+                return outcome;
+            }
+
+            attempt += 1;
+
+            if let Some(logger) = context.logger {
+                logger.warning(&format!(
+                    "Откат команды '{}' не удался, повторная попытка {} из {}",
+                    self.name, attempt, self.rollback_retries
+                ));
+            }
+
+            if let Some(policy) = &self.rollback_retry_policy {
+                tokio::time::sleep(policy.delay(attempt)).await;
+            }
+        }
     }
 
     fn name(&self) -> &str {
@@ -336,6 +1884,28 @@ impl CommandExecution for ShellCommand {
     fn supports_rollback(&self) -> bool {
         self.supports_rollback
     }
+
+    fn skip_rollback(&self) -> bool {
+        self.skip_rollback
+    }
+
+    fn dependencies(&self) -> Vec<String> {
+        self.depends_on.clone()
+    }
+
+    fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    fn rollback_preview(&self) -> Option<String> {
+        if !self.supports_rollback {
+            return None;
+        }
+
+        self.rollback_command
+            .as_ref()
+            .map(|cmd| self.preview_variables(cmd))
+    }
 }
 
 #[async_trait]
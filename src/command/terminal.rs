@@ -0,0 +1,146 @@
+use std::io::{self, BufRead, Write};
+
+/// Читает строку из стандартного ввода, не отображая вводимые символы
+///
+/// Если стандартный ввод не является терминалом (например, ввод передан через
+/// канал), строка читается как обычно.
+pub(crate) fn read_hidden_line() -> io::Result<String> {
+    let guard = EchoGuard::disable();
+
+    let mut buffer = String::new();
+    let read = io::stdin().lock().read_line(&mut buffer);
+
+    // Перевод строки после ввода не отображается терминалом без эха
+    if guard.is_active() {
+        drop(guard);
+        let mut stdout = io::stdout();
+        let _ = stdout.write_all(b"\n");
+        let _ = stdout.flush();
+    }
+
+    read?;
+    Ok(buffer)
+}
+
+/// Отключает эхо терминала и восстанавливает его при освобождении
+#[cfg(unix)]
+struct EchoGuard {
+    /// Исходные настройки терминала (если эхо было отключено)
+    original: Option<libc::termios>,
+}
+
+#[cfg(unix)]
+impl EchoGuard {
+    /// Отключает эхо стандартного ввода, если он является терминалом
+    fn disable() -> Self {
+        let fd = libc::STDIN_FILENO;
+
+        // Безопасно: структура инициализирована нулями и заполняется tcgetattr
+        let original = unsafe {
+            let mut termios: libc::termios = std::mem::zeroed();
+
+            if libc::isatty(fd) == 0 || libc::tcgetattr(fd, &mut termios) != 0 {
+                None
+            } else {
+                let original = termios;
+                termios.c_lflag &= !libc::ECHO;
+
+                (libc::tcsetattr(fd, libc::TCSANOW, &termios) == 0).then_some(original)
+            }
+        };
+
+        Self { original }
+    }
+
+    /// Возвращает true, если эхо было отключено
+    fn is_active(&self) -> bool {
+        self.original.is_some()
+    }
+}
+
+#[cfg(unix)]
+impl Drop for EchoGuard {
+    fn drop(&mut self) {
+        if let Some(original) = &self.original {
+            // Безопасно: восстанавливаются ранее полученные настройки
+            unsafe {
+                libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, original);
+            }
+        }
+    }
+}
+
+#[cfg(windows)]
+mod console {
+    use std::ffi::c_void;
+
+    pub const STD_INPUT_HANDLE: u32 = -10i32 as u32;
+    pub const ENABLE_ECHO_INPUT: u32 = 0x0004;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        pub fn GetStdHandle(std_handle: u32) -> *mut c_void;
+        pub fn GetConsoleMode(handle: *mut c_void, mode: *mut u32) -> i32;
+        pub fn SetConsoleMode(handle: *mut c_void, mode: u32) -> i32;
+    }
+}
+
+/// Отключает эхо консоли и восстанавливает его при освобождении
+#[cfg(windows)]
+struct EchoGuard {
+    /// Дескриптор консоли и ее исходный режим (если эхо было отключено)
+    original: Option<(*mut std::ffi::c_void, u32)>,
+}
+
+#[cfg(windows)]
+impl EchoGuard {
+    /// Отключает эхо стандартного ввода, если он является консолью
+    fn disable() -> Self {
+        // Безопасно: функции получают корректный дескриптор и указатель на локальную переменную
+        let original = unsafe {
+            let handle = console::GetStdHandle(console::STD_INPUT_HANDLE);
+            let mut mode = 0;
+
+            if handle.is_null() || console::GetConsoleMode(handle, &mut mode) == 0 {
+                None
+            } else {
+                let disabled = mode & !console::ENABLE_ECHO_INPUT;
+                (console::SetConsoleMode(handle, disabled) != 0).then_some((handle, mode))
+            }
+        };
+
+        Self { original }
+    }
+
+    /// Возвращает true, если эхо было отключено
+    fn is_active(&self) -> bool {
+        self.original.is_some()
+    }
+}
+
+#[cfg(windows)]
+impl Drop for EchoGuard {
+    fn drop(&mut self) {
+        if let Some((handle, mode)) = self.original {
+            // Безопасно: восстанавливается ранее полученный режим консоли
+            unsafe {
+                console::SetConsoleMode(handle, mode);
+            }
+        }
+    }
+}
+
+/// На остальных платформах эхо не отключается
+#[cfg(not(any(unix, windows)))]
+struct EchoGuard;
+
+#[cfg(not(any(unix, windows)))]
+impl EchoGuard {
+    fn disable() -> Self {
+        Self
+    }
+
+    fn is_active(&self) -> bool {
+        false
+    }
+}
@@ -1,15 +1,19 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fmt;
+use std::sync::Arc;
 use thiserror::Error;
 use uuid::Uuid;
 
+use crate::command::context::ExecutionContext;
 use crate::visitor::Visitor;
 
 /// Режим выполнения команды
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ExecutionMode {
     /// Последовательное выполнение
+    #[default]
     Sequential,
     /// Параллельное выполнение
     Parallel,
@@ -24,16 +28,35 @@ pub enum CommandError {
     #[error("Ошибка отката: {0}")]
     RollbackError(String),
 
-    #[error("Таймаут выполнения")]
-    TimeoutError,
+    #[error("Таймаут выполнения: {0}")]
+    TimeoutError(String),
 
     #[error("Команда прервана: {0}")]
     Interrupted(String),
 
+    #[error("Ошибка запуска процесса: {0}")]
+    SpawnError(String),
+
     #[error("Ошибка ввода/вывода: {0}")]
     IoError(#[from] std::io::Error),
 }
 
+/// Итоговое состояние выполнения команды
+///
+/// По умолчанию `Failed`: результат без явного состояния не считается успешным.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CommandStatus {
+    /// Команда выполнена успешно
+    Success,
+    /// Команда завершилась с ошибкой
+    #[default]
+    Failed,
+    /// Команда прервана по таймауту
+    TimedOut,
+    /// Команда пропущена
+    Skipped,
+}
+
 /// Результат выполнения команды
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CommandResult {
@@ -43,9 +66,13 @@ pub struct CommandResult {
     /// Имя команды
     pub command_name: String,
 
-    /// Успешность выполнения
+    /// Успешность выполнения (`true` для статусов `Success` и `Skipped`)
     pub success: bool,
 
+    /// Итоговое состояние выполнения
+    #[serde(default)]
+    pub status: CommandStatus,
+
     /// Вывод команды
     pub output: String,
 
@@ -63,6 +90,83 @@ pub struct CommandResult {
 
     /// Длительность выполнения в миллисекундах
     pub duration_ms: u64,
+
+    /// Структурированные данные, извлеченные из вывода
+    #[serde(default)]
+    pub data: Option<serde_json::Value>,
+
+    /// Время ожидания запуска в очереди в миллисекундах
+    #[serde(default)]
+    pub queued_ms: u64,
+
+    /// Время выполнения процесса в миллисекундах
+    #[serde(default)]
+    pub exec_ms: u64,
+
+    /// Время ожидания между повторными попытками в миллисекундах
+    #[serde(default)]
+    pub retry_wait_ms: u64,
+
+    /// Время от начала выполнения до запуска процесса в миллисекундах
+    /// (включает подстановку переменных, в том числе интерактивный ввод)
+    #[serde(default)]
+    pub spawn_latency_ms: u64,
+
+    /// Переменные, захваченные из вывода команды
+    #[serde(default)]
+    pub captured: HashMap<String, String>,
+
+    /// Вывод команды был обрезан из-за превышения ограничения размера
+    #[serde(default)]
+    pub truncated: bool,
+
+    /// Процессорное время процесса в миллисекундах (если доступно на платформе)
+    #[serde(default)]
+    pub cpu_time_ms: Option<u64>,
+
+    /// Пиковый размер резидентной памяти процесса в килобайтах (если доступно на платформе)
+    #[serde(default)]
+    pub max_rss_kb: Option<u64>,
+
+    /// Командная строка после подстановки переменных
+    #[serde(default)]
+    pub resolved_command: String,
+
+    /// Метки выполненной команды
+    #[serde(default)]
+    pub tags: HashMap<String, String>,
+
+    /// Необработанный стандартный вывод (только для команд с двоичным выводом,
+    /// поле `output` в этом случае пустое)
+    #[serde(default)]
+    pub stdout_bytes: Vec<u8>,
+
+    /// Состояние запуска, необходимое для отката команды (не сериализуется)
+    #[serde(skip)]
+    pub(crate) rollback_state: Option<Arc<RollbackState>>,
+}
+
+/// Состояние запуска команды, необходимое для ее отката
+///
+/// Хранится в результате запуска, а не в самой команде, чтобы одновременные
+/// запуски одной команды не влияли на откат друг друга. При откате цепочка
+/// передает результат команде через `ExecutionContext::forward_result`.
+#[derive(Clone, Default)]
+pub(crate) struct RollbackState {
+    /// Вложенные команды, выполненные при запуске, и их результаты
+    pub executed: Vec<(Arc<dyn Command>, CommandResult)>,
+
+    /// Вложенные команды запуска уже откачены самой командой
+    pub rolled_back: bool,
+}
+
+impl fmt::Debug for RollbackState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RollbackState")
+            .field("executed", &self.executed.len())
+            .field("rolled_back", &self.rolled_back)
+            .finish()
+    }
 }
 
 impl CommandResult {
@@ -73,18 +177,66 @@ impl CommandResult {
             id: Uuid::new_v4().to_string(),
             command_name: command_name.to_string(),
             success: false,
+            status: CommandStatus::Failed,
             output: String::new(),
             error: None,
             exit_code: None,
             start_time: now,
             end_time: now,
             duration_ms: 0,
+            data: None,
+            queued_ms: 0,
+            exec_ms: 0,
+            retry_wait_ms: 0,
+            spawn_latency_ms: 0,
+            captured: HashMap::new(),
+            truncated: false,
+            cpu_time_ms: None,
+            max_rss_kb: None,
+            resolved_command: String::new(),
+            tags: HashMap::new(),
+            stdout_bytes: Vec::new(),
+            rollback_state: None,
         }
     }
 
+    /// Сохраняет состояние запуска, необходимое для отката команды
+    pub(crate) fn with_rollback_state(mut self, state: RollbackState) -> Self {
+        self.rollback_state = Some(Arc::new(state));
+        self
+    }
+
+    /// Возвращает состояние запуска, необходимое для отката команды (если есть)
+    pub(crate) fn rollback_state(&self) -> Option<&RollbackState> {
+        self.rollback_state.as_deref()
+    }
+
+    /// Отмечает команду как пропущенную (пропуск не считается ошибкой)
+    pub fn skip(mut self, reason: &str) -> Self {
+        self.success = true;
+        self.status = CommandStatus::Skipped;
+        self.output = reason.to_string();
+        self.end_time = chrono::Utc::now();
+        self.duration_ms = (self.end_time - self.start_time).num_milliseconds() as u64;
+        self
+    }
+
+    /// Устанавливает код возврата
+    pub fn with_exit_code(mut self, exit_code: Option<i32>) -> Self {
+        self.exit_code = exit_code;
+        self
+    }
+
+    /// Устанавливает структурированные данные результата
+    pub fn with_data(mut self, data: Option<serde_json::Value>) -> Self {
+        self.data = data;
+        self
+    }
+
     /// Отмечает результат как успешный
     pub fn success(mut self, output: String) -> Self {
         self.success = true;
+        self.status = CommandStatus::Success;
         self.output = output;
         self.end_time = chrono::Utc::now();
         self.duration_ms = (self.end_time - self.start_time).num_milliseconds() as u64;
@@ -94,12 +246,32 @@ impl CommandResult {
     /// Отмечает результат как неудачный
     pub fn failure(mut self, error: String, exit_code: Option<i32>) -> Self {
         self.success = false;
+        self.status = CommandStatus::Failed;
         self.error = Some(error);
         self.exit_code = exit_code;
         self.end_time = chrono::Utc::now();
         self.duration_ms = (self.end_time - self.start_time).num_milliseconds() as u64;
         self
     }
+
+    /// Отмечает результат как прерванный по таймауту
+    pub fn timeout(mut self, error: String) -> Self {
+        self = self.failure(error, None);
+        self.status = CommandStatus::TimedOut;
+        self
+    }
+
+    /// Возвращает true, если команда прервана по таймауту
+    ///
+    /// Вывод такой команды содержит полученное до прерывания.
+    pub fn is_timeout(&self) -> bool {
+        self.status == CommandStatus::TimedOut
+    }
+
+    /// Возвращает true, если команда пропущена
+    pub fn is_skipped(&self) -> bool {
+        self.status == CommandStatus::Skipped
+    }
 }
 
 impl fmt::Display for CommandResult {
@@ -125,6 +297,14 @@ pub trait CommandExecution {
     /// Выполняет команду
     async fn execute(&self) -> Result<CommandResult, CommandError>;
 
+    /// Выполняет команду с учетом контекста выполнения
+    async fn execute_with_context(
+        &self,
+        _context: &ExecutionContext<'_>,
+    ) -> Result<CommandResult, CommandError> {
+        self.execute().await
+    }
+
     /// Выполняет откат команды, если это возможно
     async fn rollback(&self) -> Result<CommandResult, CommandError> {
         Err(CommandError::RollbackError(
@@ -132,6 +312,17 @@ pub trait CommandExecution {
         ))
     }
 
+    /// Выполняет откат команды с учетом контекста выполнения
+    ///
+    /// Контекст содержит переменные, захваченные при выполнении команд цепочки,
+    /// которые могут использоваться в команде отката.
+    async fn rollback_with_context(
+        &self,
+        _context: &ExecutionContext<'_>,
+    ) -> Result<CommandResult, CommandError> {
+        self.rollback().await
+    }
+
     /// Возвращает имя команды
     fn name(&self) -> &str;
 
@@ -142,11 +333,43 @@ pub trait CommandExecution {
     fn supports_rollback(&self) -> bool {
         false
     }
+
+    /// Возвращает информацию, исключена ли команда из отката цепочки
+    fn skip_rollback(&self) -> bool {
+        false
+    }
+
+    /// Возвращает имена команд, после успешного выполнения которых запускается команда
+    fn dependencies(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Возвращает приоритет запуска при параллельном выполнении с ограничением
+    /// числа одновременных команд (большее значение запускается раньше)
+    fn priority(&self) -> i32 {
+        0
+    }
+
+    /// Возвращает описание отката команды без его выполнения (если откат поддерживается)
+    fn rollback_preview(&self) -> Option<String> {
+        None
+    }
 }
 
 /// Основной трейт команды
 #[async_trait]
 pub trait Command: CommandExecution + Send + Sync {
     /// Принимает визитор для реализации паттерна посетитель
-    fn accept(&self, visitor: &mut dyn Visitor);
+    ///
+    /// По умолчанию команда посещается как пользовательская через
+    /// `Visitor::visit_generic_command`.
+    fn accept(&self, visitor: &mut dyn Visitor) {
+        visitor.visit_generic_command(self.name(), self.kind());
+    }
+
+    /// Возвращает вид команды (по умолчанию — имя типа без пути модуля)
+    fn kind(&self) -> &str {
+        let type_name = std::any::type_name::<Self>();
+        type_name.rsplit("::").next().unwrap_or(type_name)
+    }
 }
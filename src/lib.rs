@@ -5,8 +5,18 @@ pub mod logging;
 pub mod visitor;
 
 // Реэкспорт основных компонентов для удобства использования
-pub use builder::{ChainBuilder, CommandBuilder};
-pub use chain::{ChainExecutionMode, CommandChain};
-pub use command::{Command, CommandExecution, CommandResult, ExecutionMode};
-pub use logging::{ConsoleLogger, FileLogger, LogLevel, Logger, LoggingStrategy};
-pub use visitor::{LogVisitor, Visitor};
+pub use builder::{
+    BuildError, ChainBuilder, CommandBuilder, CompositeCommandBuilder, LoggerBuilder,
+};
+pub use chain::{
+    ChainCommand, ChainEvent, ChainExecutionMode, ChainResult, ChainSummary, CommandChain,
+};
+pub use command::{
+    Command, CommandExecution, CommandResult, CommandStatus, ConditionalCommand, ExecutionContext,
+    ExecutionMode, ParsedResult,
+};
+pub use logging::{
+    AsyncFileLogger, ConsoleLogger, DedupLogger, FileLogger, FilteringLogger, LogFormatter,
+    LogLevel, Logger, LoggingStrategy, NullLogger, RedactingLogger,
+};
+pub use visitor::{LogVisitor, MetricsVisitor, ValidationVisitor, Visitor};
@@ -0,0 +1,134 @@
+use std::path::Path;
+use std::sync::Mutex;
+
+use tokio::fs::OpenOptions;
+use tokio::io::{AsyncWriteExt, BufWriter};
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::task::JoinHandle;
+
+use crate::logging::file_logger::json_entry;
+use crate::logging::traits::{LogContext, LogLevel, Logger};
+
+/// Логгер, записывающий JSON-записи в файл из отдельной задачи
+///
+/// Метод `log` только сериализует запись и передает ее в канал, не блокируя
+/// поток выполнения. Запись в файл выполняет фоновая задача; для гарантии
+/// записи всех сообщений перед завершением программы вызовите `shutdown`.
+/// Логгер должен создаваться внутри среды выполнения tokio.
+pub struct AsyncFileLogger {
+    /// Минимальный уровень логирования
+    min_level: LogLevel,
+
+    /// Канал передачи строк в задачу записи (закрывается при остановке)
+    sender: Mutex<Option<UnboundedSender<String>>>,
+
+    /// Задача записи в файл
+    writer_task: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl AsyncFileLogger {
+    /// Создает новый асинхронный файловый логгер и запускает задачу записи
+    pub fn new(min_level: LogLevel, file_path: &str) -> Self {
+        // Создаем директорию для логов, если ее нет
+        if let Some(parent) = Path::new(file_path).parent() {
+            if !parent.exists() {
+                let _ = std::fs::create_dir_all(parent);
+            }
+        }
+
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let writer_task = tokio::spawn(Self::write_loop(file_path.to_string(), receiver));
+
+        Self {
+            min_level,
+            sender: Mutex::new(Some(sender)),
+            writer_task: Mutex::new(Some(writer_task)),
+        }
+    }
+
+    /// Закрывает канал и дожидается записи всех отправленных сообщений
+    ///
+    /// Сообщения, отправленные после остановки, отбрасываются.
+    pub async fn shutdown(&self) {
+        self.sender.lock().unwrap_or_else(|e| e.into_inner()).take();
+
+        let writer_task = self
+            .writer_task
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take();
+
+        if let Some(writer_task) = writer_task {
+            if let Err(err) = writer_task.await {
+                eprintln!("Ошибка задачи записи в файл логов: {}", err);
+            }
+        }
+    }
+
+    /// Записывает полученные строки в файл до закрытия канала
+    async fn write_loop(file_path: String, mut receiver: UnboundedReceiver<String>) {
+        let file = match OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&file_path)
+            .await
+        {
+            Ok(file) => file,
+            Err(err) => {
+                eprintln!("Ошибка открытия файла логов '{}': {}", file_path, err);
+                return;
+            }
+        };
+
+        let mut writer = BufWriter::new(file);
+
+        while let Some(line) = receiver.recv().await {
+            if let Err(err) = writer.write_all(line.as_bytes()).await {
+                eprintln!("Ошибка записи в файл логов: {}", err);
+                continue;
+            }
+
+            // Сбрасываем буфер, когда очередь сообщений опустела
+            if receiver.is_empty() {
+                if let Err(err) = writer.flush().await {
+                    eprintln!("Ошибка сброса буфера файла логов: {}", err);
+                }
+            }
+        }
+
+        if let Err(err) = writer.flush().await {
+            eprintln!("Ошибка сброса буфера файла логов: {}", err);
+        }
+    }
+
+    /// Отправляет JSON-запись в задачу записи
+    fn send(&self, log_entry: serde_json::Value) {
+        let log_line = match serde_json::to_string(&log_entry) {
+            Ok(json) => format!("{}\n", json),
+            Err(err) => {
+                eprintln!("Ошибка сериализации записи лога: {}", err);
+                return;
+            }
+        };
+
+        let sender = self.sender.lock().unwrap_or_else(|e| e.into_inner());
+
+        if let Some(sender) = sender.as_ref() {
+            let _ = sender.send(log_line);
+        }
+    }
+}
+
+impl Logger for AsyncFileLogger {
+    fn log(&self, level: LogLevel, message: &str) {
+        if level as u8 >= self.min_level as u8 {
+            self.send(json_entry(level, message, None));
+        }
+    }
+
+    fn log_with_context(&self, level: LogLevel, message: &str, context: &LogContext) {
+        if level as u8 >= self.min_level as u8 {
+            self.send(json_entry(level, message, Some(context)));
+        }
+    }
+}
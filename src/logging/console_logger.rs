@@ -1,19 +1,94 @@
-use chrono::Local;
-use colored::*;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::io::{IsTerminal, Write};
 use std::sync::Mutex;
 
-use crate::logging::traits::{LogContext, LogLevel, Logger};
+use crate::logging::formatter::{DefaultFormatter, JsonFormatter, LogFormatter};
+use crate::logging::traits::{LogContext, LogLevel, Logger, OutputStream};
+
+/// Escape-последовательность выделения строк stderr команд (тусклый красный)
+///
+/// Задается явно, а не через `colored`, который сам отключает цвета вне терминала
+/// и не учитывает явное включение цветов через `with_colors`.
+const STDERR_OUTPUT_STYLE: &str = "\x1b[2;31m";
+
+/// Escape-последовательность сброса оформления
+const RESET_STYLE: &str = "\x1b[0m";
+
+lazy_static! {
+    static ref ANSI_ESCAPE_PATTERN: Regex = Regex::new(r"\x1b\[[0-9;]*m").unwrap();
+}
+
+/// Поток консоли, в который выводятся сообщения
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConsoleTarget {
+    /// Все сообщения выводятся в stdout
+    Stdout,
+    /// Все сообщения выводятся в stderr
+    Stderr,
+    /// Предупреждения и ошибки выводятся в stderr, остальные сообщения в stdout
+    #[default]
+    Split,
+}
+
+impl ConsoleTarget {
+    /// Определяет, выводится ли сообщение указанного уровня в stderr
+    fn is_stderr(self, level: LogLevel) -> bool {
+        match self {
+            ConsoleTarget::Stdout => false,
+            ConsoleTarget::Stderr => true,
+            ConsoleTarget::Split => level as u8 >= LogLevel::Warning as u8,
+        }
+    }
+}
+
+/// Пользовательские приемники вывода консольного логгера
+#[derive(Default)]
+struct Writers {
+    /// Приемник всех сообщений
+    all: Option<Box<dyn Write + Send>>,
+
+    /// Приемник сообщений, направляемых в stderr (имеет приоритет над `all`)
+    stderr: Option<Box<dyn Write + Send>>,
+}
+
+impl Writers {
+    /// Возвращает приемник для сообщения (`None` - вывод в консоль)
+    fn select(&mut self, to_stderr: bool) -> Option<&mut Box<dyn Write + Send>> {
+        if to_stderr {
+            if let Some(writer) = self.stderr.as_mut() {
+                return Some(writer);
+            }
+        }
+
+        self.all.as_mut()
+    }
+}
 
 /// Структура для логирования в консоль с поддержкой цветов
 pub struct ConsoleLogger {
     /// Минимальный уровень логирования
     min_level: LogLevel,
 
-    /// Формат времени
-    time_format: String,
+    /// Форматировщик строк лога
+    formatter: Box<dyn LogFormatter>,
 
-    /// Мьютекс для синхронизации вывода
-    output_mutex: Mutex<()>,
+    /// Пользовательские приемники вывода (если не заданы, используются stdout и stderr)
+    ///
+    /// Мьютекс также синхронизирует вывод в консоль.
+    writers: Mutex<Writers>,
+
+    /// Выделять ли цветом строки stderr при выводе команд в терминал
+    color_output: bool,
+
+    /// Поток консоли для вывода сообщений
+    target: ConsoleTarget,
+
+    /// Явное включение или отключение цветов (по умолчанию определяется автоматически)
+    colors: Option<bool>,
+
+    /// Передавать ли строки вывода команд форматировщику (режим JSON)
+    format_output: bool,
 }
 
 impl ConsoleLogger {
@@ -21,83 +96,187 @@ impl ConsoleLogger {
     pub fn new(min_level: LogLevel) -> Self {
         Self {
             min_level,
-            time_format: "%Y-%m-%d %H:%M:%S%.3f".to_string(),
-            output_mutex: Mutex::new(()),
+            formatter: Box::new(DefaultFormatter::new()),
+            writers: Mutex::new(Writers::default()),
+            color_output: true,
+            target: ConsoleTarget::default(),
+            colors: None,
+            format_output: false,
         }
     }
 
+    /// Создает консольный логгер, выводящий в stdout JSON-строки в формате `FileLogger`
+    ///
+    /// Строки вывода команд также выводятся как JSON-записи: stdout на уровне
+    /// `Info`, stderr на уровне `Warning`.
+    pub fn json(min_level: LogLevel) -> Self {
+        let mut logger = Self::new(min_level)
+            .with_formatter(Box::new(JsonFormatter::new()))
+            .with_stream(ConsoleTarget::Stdout)
+            .with_colors(false);
+        logger.format_output = true;
+        logger
+    }
+
     /// Устанавливает формат времени
+    ///
+    /// Заменяет форматировщик стандартным с указанным форматом времени.
     pub fn with_time_format(mut self, format: &str) -> Self {
-        self.time_format = format.to_string();
+        self.formatter = Box::new(DefaultFormatter::new().with_time_format(format));
+        self
+    }
+
+    /// Устанавливает пользовательский форматировщик строк лога
+    pub fn with_formatter(mut self, formatter: Box<dyn LogFormatter>) -> Self {
+        self.formatter = formatter;
         self
     }
 
-    /// Возвращает цветной текст для уровня логирования
-    fn get_colored_level(&self, level: LogLevel) -> ColoredString {
-        match level {
-            LogLevel::Debug => "DEBUG".cyan(),
-            LogLevel::Info => "INFO".green(),
-            LogLevel::Warning => "WARNING".yellow(),
-            LogLevel::Error => "ERROR".red(),
-            LogLevel::Critical => "CRITICAL".red().bold(),
+    /// Устанавливает флаг выделения цветом строк stderr при выводе команд
+    pub fn with_output_coloring(mut self, enabled: bool) -> Self {
+        self.color_output = enabled;
+        self
+    }
+
+    /// Устанавливает поток консоли для вывода сообщений
+    ///
+    /// По умолчанию предупреждения и ошибки выводятся в stderr, остальные сообщения в stdout.
+    pub fn with_stream(mut self, target: ConsoleTarget) -> Self {
+        self.target = target;
+        self
+    }
+
+    /// Устанавливает приемник, в который записываются все сообщения вместо консоли
+    ///
+    /// Приемник не считается терминалом, поэтому escape-последовательности
+    /// цветов из строк удаляются (если цвета не включены через `with_colors`).
+    pub fn with_writer(self, writer: Box<dyn Write + Send>) -> Self {
+        self.writers.lock().unwrap_or_else(|e| e.into_inner()).all = Some(writer);
+        self
+    }
+
+    /// Устанавливает приемник сообщений, направляемых в stderr (см. `with_stream`)
+    ///
+    /// Остальные сообщения выводятся в приемник `with_writer` или в stdout.
+    pub fn with_stderr_writer(self, writer: Box<dyn Write + Send>) -> Self {
+        self.writers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .stderr = Some(writer);
+        self
+    }
+
+    /// Явно включает или отключает цвета
+    ///
+    /// По умолчанию цвета используются только при выводе в терминал и если
+    /// не установлена переменная окружения `NO_COLOR`.
+    pub fn with_colors(mut self, enabled: bool) -> Self {
+        self.colors = Some(enabled);
+        self
+    }
+
+    /// Проверяет, используются ли цвета при выводе строки
+    fn colors_enabled(&self, custom_writer: bool, to_stderr: bool) -> bool {
+        if let Some(enabled) = self.colors {
+            return enabled;
         }
+
+        // Соглашение https://no-color.org: учитывается только непустое значение
+        let no_color = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+
+        let is_terminal = if custom_writer {
+            false
+        } else if to_stderr {
+            std::io::stderr().is_terminal()
+        } else {
+            std::io::stdout().is_terminal()
+        };
+
+        !no_color && is_terminal
     }
-}
 
-impl Logger for ConsoleLogger {
-    fn log(&self, level: LogLevel, message: &str) {
-        // Проверяем, нужно ли логировать это сообщение
-        if level as u8 >= self.min_level as u8 {
-            // Блокируем мьютекс для избежания смешивания вывода
-            let _lock = self.output_mutex.lock().unwrap_or_else(|e| e.into_inner());
-
-            // Форматируем время
-            let now = Local::now();
-            let formatted_time = now.format(&self.time_format).to_string();
-
-            // Выводим отформатированное сообщение
-            println!(
-                "{} [{}] {}",
-                formatted_time,
-                self.get_colored_level(level),
-                message
-            );
+    /// Выводит строку в приемник либо в stdout или stderr, удаляя цвета при необходимости
+    fn write_line(
+        writer: Option<&mut Box<dyn Write + Send>>,
+        to_stderr: bool,
+        colors: bool,
+        line: &str,
+    ) {
+        let line = if colors {
+            line.into()
+        } else {
+            ANSI_ESCAPE_PATTERN.replace_all(line, "")
+        };
+
+        match writer {
+            Some(writer) => {
+                if let Err(err) = writeln!(writer, "{}", line) {
+                    eprintln!("Ошибка записи лога: {}", err);
+                }
+            }
+            None if to_stderr => eprintln!("{}", line),
+            None => println!("{}", line),
         }
     }
 
-    fn log_with_context(&self, level: LogLevel, message: &str, context: &LogContext) {
+    /// Выводит сообщение, отформатированное форматировщиком
+    fn write_formatted(&self, level: LogLevel, message: &str, context: Option<&LogContext>) {
         // Проверяем, нужно ли логировать это сообщение
         if level as u8 >= self.min_level as u8 {
+            let line = self.formatter.format(level, message, context);
+
             // Блокируем мьютекс для избежания смешивания вывода
-            let _lock = self.output_mutex.lock().unwrap_or_else(|e| e.into_inner());
+            let mut writers = self.writers.lock().unwrap_or_else(|e| e.into_inner());
 
-            // Форматируем время
-            let now = Local::now();
-            let formatted_time = now.format(&self.time_format).to_string();
+            let to_stderr = self.target.is_stderr(level);
+            let colors = self.colors_enabled(writers.select(to_stderr).is_some(), to_stderr);
 
-            // Добавляем информацию о местоположении, если есть
-            let location = if let (Some(file), Some(line)) = (&context.file, context.line) {
-                format!(" ({}: {})", file, line)
-            } else {
-                String::new()
+            Self::write_line(writers.select(to_stderr), to_stderr, colors, &line);
+        }
+    }
+}
+
+impl Logger for ConsoleLogger {
+    fn log(&self, level: LogLevel, message: &str) {
+        self.write_formatted(level, message, None);
+    }
+
+    fn log_with_context(&self, level: LogLevel, message: &str, context: &LogContext) {
+        self.write_formatted(level, message, Some(context));
+    }
+
+    fn log_output(&self, stream: OutputStream, line: &str) {
+        if self.format_output {
+            let level = match stream {
+                OutputStream::Stdout => LogLevel::Info,
+                OutputStream::Stderr => LogLevel::Warning,
             };
 
-            // Добавляем вызывающего, если есть
-            let caller = if let Some(caller) = &context.caller {
-                format!(" [{}]", caller)
-            } else {
-                String::new()
+            self.write_formatted(level, line, None);
+            return;
+        }
+
+        // Строки вывода команд логируются на информационном уровне
+        if LogLevel::Info as u8 >= self.min_level as u8 {
+            let mut writers = self.writers.lock().unwrap_or_else(|e| e.into_inner());
+
+            // Строки stderr команды направляются как предупреждения
+            let to_stderr = match stream {
+                OutputStream::Stdout => self.target.is_stderr(LogLevel::Info),
+                OutputStream::Stderr => self.target.is_stderr(LogLevel::Warning),
             };
 
-            // Выводим отформатированное сообщение
-            println!(
-                "{} [{}]{}{} {}",
-                formatted_time,
-                self.get_colored_level(level),
-                location,
-                caller,
-                message
-            );
+            // Цвет используется только при выводе в терминал
+            let colors = self.colors_enabled(writers.select(to_stderr).is_some(), to_stderr);
+            let writer = writers.select(to_stderr);
+
+            match stream {
+                OutputStream::Stderr if colors && self.color_output => {
+                    let line = format!("{}{}{}", STDERR_OUTPUT_STYLE, line, RESET_STYLE);
+                    Self::write_line(writer, to_stderr, colors, &line)
+                }
+                _ => Self::write_line(writer, to_stderr, colors, line),
+            }
         }
     }
 }
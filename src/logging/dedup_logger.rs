@@ -0,0 +1,106 @@
+use std::sync::Mutex;
+
+use crate::logging::traits::{LogContext, LogLevel, Logger, OutputStream};
+
+/// Последнее переданное сообщение и число его подавленных повторов
+struct LastMessage {
+    /// Уровень сообщения
+    level: LogLevel,
+
+    /// Текст сообщения
+    message: String,
+
+    /// Число повторов, не переданных внутреннему логгеру
+    repeats: usize,
+}
+
+/// Логгер-обертка, подавляющий подряд идущие одинаковые сообщения
+///
+/// Повтор сообщения с тем же уровнем и текстом не передается внутреннему логгеру.
+/// Когда приходит другое сообщение (или вызывается `flush`), внутреннему логгеру
+/// передается строка с числом подавленных повторов. Строки вывода команд
+/// передаются без изменений.
+pub struct DedupLogger {
+    /// Логгер, получающий сообщения без повторов
+    inner: Box<dyn Logger>,
+
+    /// Последнее переданное сообщение
+    last: Mutex<Option<LastMessage>>,
+}
+
+impl DedupLogger {
+    /// Создает логгер, подавляющий повторы сообщений
+    pub fn new(inner: Box<dyn Logger>) -> Self {
+        Self {
+            inner,
+            last: Mutex::new(None),
+        }
+    }
+
+    /// Передает внутреннему логгеру строку с числом подавленных повторов, если они были
+    pub fn flush(&self) {
+        let mut last = self.last.lock().unwrap_or_else(|e| e.into_inner());
+        self.report_repeats(&mut last);
+    }
+
+    /// Записывает число подавленных повторов последнего сообщения и сбрасывает его
+    fn report_repeats(&self, last: &mut Option<LastMessage>) {
+        if let Some(last) = last {
+            if last.repeats > 0 {
+                self.inner.log(
+                    last.level,
+                    &format!("(повторов предыдущего сообщения: {})", last.repeats),
+                );
+                last.repeats = 0;
+            }
+        }
+    }
+
+    /// Передает сообщение внутреннему логгеру, если оно не повторяет предыдущее
+    fn log_once<F>(&self, level: LogLevel, message: &str, log: F)
+    where
+        F: FnOnce(&dyn Logger),
+    {
+        // Блокировка удерживается при записи, чтобы сохранить порядок сообщений
+        let mut last = self.last.lock().unwrap_or_else(|e| e.into_inner());
+
+        if let Some(last) = last.as_mut() {
+            if last.level == level && last.message == message {
+                last.repeats += 1;
+                return;
+            }
+        }
+
+        self.report_repeats(&mut last);
+        log(self.inner.as_ref());
+
+        *last = Some(LastMessage {
+            level,
+            message: message.to_string(),
+            repeats: 0,
+        });
+    }
+}
+
+impl Logger for DedupLogger {
+    fn log(&self, level: LogLevel, message: &str) {
+        self.log_once(level, message, |inner| inner.log(level, message));
+    }
+
+    fn log_with_context(&self, level: LogLevel, message: &str, context: &LogContext) {
+        self.log_once(level, message, |inner| {
+            inner.log_with_context(level, message, context)
+        });
+    }
+
+    fn log_output(&self, stream: OutputStream, line: &str) {
+        self.flush();
+        self.inner.log_output(stream, line);
+    }
+}
+
+impl Drop for DedupLogger {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
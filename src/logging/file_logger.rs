@@ -1,12 +1,15 @@
 use chrono::{DateTime, Local, Utc};
 use serde_json::json;
 use std::fs::{File, OpenOptions};
-use std::io::Write;
+use std::io::{BufWriter, Write};
 use std::path::Path;
 use std::sync::Mutex;
 
 use crate::logging::traits::{LogContext, LogLevel, Logger};
 
+/// Размер буфера, при заполнении которого записи сбрасываются в файл
+const BUFFER_CAPACITY: usize = 64 * 1024;
+
 /// Структура для логирования в файл в формате JSON
 pub struct FileLogger {
     /// Минимальный уровень логирования
@@ -15,8 +18,8 @@ pub struct FileLogger {
     /// Путь к файлу логов
     file_path: String,
 
-    /// Мьютекс для синхронизации записи в файл
-    file_mutex: Mutex<()>,
+    /// Буферизованный поток записи в файл (открывается при первой записи)
+    writer: Mutex<Option<BufWriter<File>>>,
 }
 
 impl FileLogger {
@@ -32,7 +35,7 @@ impl FileLogger {
         Self {
             min_level,
             file_path: file_path.to_string(),
-            file_mutex: Mutex::new(()),
+            writer: Mutex::new(None),
         }
     }
 
@@ -44,40 +47,47 @@ impl FileLogger {
             .open(&self.file_path)
     }
 
-    /// Записывает JSON-сообщение в файл
+    /// Записывает JSON-сообщение в буфер файла
+    ///
+    /// Записи сбрасываются в файл при заполнении буфера, при вызове `flush`
+    /// и при уничтожении логгера.
     fn write_json_log(&self, log_entry: serde_json::Value) -> std::io::Result<()> {
+        // Сериализуем JSON до блокировки, чтобы строка записывалась целиком
+        let log_line = format!("{}\n", serde_json::to_string(&log_entry)?);
+
         // Блокируем мьютекс для синхронизации записи
-        let _lock = self.file_mutex.lock().unwrap_or_else(|e| e.into_inner());
+        let mut writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
 
-        // Открываем файл логов
-        let mut file = self.open_log_file()?;
+        // Открываем файл логов при первой записи
+        if writer.is_none() {
+            let file = self.open_log_file()?;
+            *writer = Some(BufWriter::with_capacity(BUFFER_CAPACITY, file));
+        }
 
-        // Сериализуем JSON и записываем в файл
-        let log_json = serde_json::to_string(&log_entry)?;
-        writeln!(file, "{}", log_json)?;
+        if let Some(writer) = writer.as_mut() {
+            writer.write_all(log_line.as_bytes())?;
+        }
 
         Ok(())
     }
+
+    /// Сбрасывает накопленные записи в файл
+    pub fn flush(&self) -> std::io::Result<()> {
+        let mut writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+
+        match writer.as_mut() {
+            Some(writer) => writer.flush(),
+            None => Ok(()),
+        }
+    }
 }
 
 impl Logger for FileLogger {
     fn log(&self, level: LogLevel, message: &str) {
         // Проверяем, нужно ли логировать это сообщение
         if level as u8 >= self.min_level as u8 {
-            // Текущее время в разных форматах
-            let now: DateTime<Utc> = Utc::now();
-            let local_time = Local::now();
-
-            // Создаем JSON запись
-            let log_entry = json!({
-                "timestamp": now.to_rfc3339(),
-                "local_time": local_time.format("%Y-%m-%d %H:%M:%S%.3f").to_string(),
-                "level": level.as_str(),
-                "message": message,
-            });
-
             // Пишем в файл
-            if let Err(err) = self.write_json_log(log_entry) {
+            if let Err(err) = self.write_json_log(json_entry(level, message, None)) {
                 eprintln!("Ошибка записи в файл логов: {}", err);
             }
         }
@@ -86,39 +96,57 @@ impl Logger for FileLogger {
     fn log_with_context(&self, level: LogLevel, message: &str, context: &LogContext) {
         // Проверяем, нужно ли логировать это сообщение
         if level as u8 >= self.min_level as u8 {
-            // Текущее время в разных форматах
-            let now: DateTime<Utc> = Utc::now();
-            let local_time = Local::now();
-
-            // Создаем JSON запись с контекстом
-            let mut log_entry = json!({
-                "timestamp": now.to_rfc3339(),
-                "local_time": local_time.format("%Y-%m-%d %H:%M:%S%.3f").to_string(),
-                "level": level.as_str(),
-                "message": message,
-            });
-
-            // Добавляем контекст, если информация доступна
-            if let Some(caller) = &context.caller {
-                log_entry["caller"] = json!(caller);
+            // Пишем в файл
+            if let Err(err) = self.write_json_log(json_entry(level, message, Some(context))) {
+                eprintln!("Ошибка записи в файл логов с контекстом: {}", err);
             }
+        }
+    }
+}
 
-            if let Some(file) = &context.file {
-                log_entry["file"] = json!(file);
-            }
+/// Формирует JSON-запись лога с отметками времени и контекстом
+pub(crate) fn json_entry(
+    level: LogLevel,
+    message: &str,
+    context: Option<&LogContext>,
+) -> serde_json::Value {
+    // Текущее время в разных форматах
+    let now: DateTime<Utc> = Utc::now();
+    let local_time = Local::now();
+
+    let mut log_entry = json!({
+        "timestamp": now.to_rfc3339(),
+        "local_time": local_time.format("%Y-%m-%d %H:%M:%S%.3f").to_string(),
+        "level": level.as_str(),
+        "message": message,
+    });
+
+    // Добавляем контекст, если информация доступна
+    if let Some(context) = context {
+        if let Some(caller) = &context.caller {
+            log_entry["caller"] = json!(caller);
+        }
 
-            if let Some(line) = context.line {
-                log_entry["line"] = json!(line);
-            }
+        if let Some(file) = &context.file {
+            log_entry["file"] = json!(file);
+        }
 
-            if let Some(extra) = &context.extra {
-                log_entry["extra"] = extra.clone();
-            }
+        if let Some(line) = context.line {
+            log_entry["line"] = json!(line);
+        }
 
-            // Пишем в файл
-            if let Err(err) = self.write_json_log(log_entry) {
-                eprintln!("Ошибка записи в файл логов с контекстом: {}", err);
-            }
+        if let Some(extra) = &context.extra {
+            log_entry["extra"] = extra.clone();
+        }
+    }
+
+    log_entry
+}
+
+impl Drop for FileLogger {
+    fn drop(&mut self) {
+        if let Err(err) = self.flush() {
+            eprintln!("Ошибка сброса буфера файла логов: {}", err);
         }
     }
 }
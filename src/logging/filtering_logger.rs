@@ -0,0 +1,60 @@
+use crate::logging::traits::{LogContext, LogLevel, Logger, OutputStream};
+
+/// Условие, которому должно удовлетворять сообщение для передачи в логгер
+pub type LogFilter = Box<dyn Fn(LogLevel, &str) -> bool + Send + Sync>;
+
+/// Логгер-обертка, передающий внутреннему логгеру только сообщения,
+/// удовлетворяющие условию
+pub struct FilteringLogger {
+    /// Логгер, получающий отфильтрованные сообщения
+    inner: Box<dyn Logger>,
+
+    /// Условие фильтрации
+    filter: LogFilter,
+}
+
+impl FilteringLogger {
+    /// Создает логгер с произвольным условием фильтрации
+    pub fn new<F>(inner: Box<dyn Logger>, filter: F) -> Self
+    where
+        F: Fn(LogLevel, &str) -> bool + Send + Sync + 'static,
+    {
+        Self {
+            inner,
+            filter: Box::new(filter),
+        }
+    }
+
+    /// Создает логгер, пропускающий только сообщения, содержащие подстроку
+    ///
+    /// Удобно для просмотра сообщений, относящихся к одной команде цепочки.
+    pub fn with_substring(inner: Box<dyn Logger>, needle: &str) -> Self {
+        let needle = needle.to_string();
+        Self::new(inner, move |_, message| message.contains(&needle))
+    }
+}
+
+impl Logger for FilteringLogger {
+    fn log(&self, level: LogLevel, message: &str) {
+        if (self.filter)(level, message) {
+            self.inner.log(level, message);
+        }
+    }
+
+    fn log_with_context(&self, level: LogLevel, message: &str, context: &LogContext) {
+        if (self.filter)(level, message) {
+            self.inner.log_with_context(level, message, context);
+        }
+    }
+
+    fn log_output(&self, stream: OutputStream, line: &str) {
+        let level = match stream {
+            OutputStream::Stdout => LogLevel::Info,
+            OutputStream::Stderr => LogLevel::Warning,
+        };
+
+        if (self.filter)(level, line) {
+            self.inner.log_output(stream, line);
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use chrono::Local;
+use colored::*;
+
+use crate::logging::file_logger::json_entry;
+use crate::logging::traits::{LogContext, LogLevel};
+
+/// Формат времени по умолчанию
+const DEFAULT_TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.3f";
+
+/// Трейт форматирования строк лога
+pub trait LogFormatter: Send + Sync {
+    /// Форматирует сообщение лога в строку (без завершающего перевода строки)
+    fn format(&self, level: LogLevel, message: &str, context: Option<&LogContext>) -> String;
+}
+
+/// Стандартный форматировщик: время, цветной уровень, местоположение и вызывающий
+#[derive(Debug, Clone)]
+pub struct DefaultFormatter {
+    /// Формат времени
+    time_format: String,
+}
+
+impl DefaultFormatter {
+    /// Создает стандартный форматировщик
+    pub fn new() -> Self {
+        Self {
+            time_format: DEFAULT_TIME_FORMAT.to_string(),
+        }
+    }
+
+    /// Устанавливает формат времени
+    pub fn with_time_format(mut self, format: &str) -> Self {
+        self.time_format = format.to_string();
+        self
+    }
+
+    /// Возвращает цветной текст для уровня логирования
+    fn get_colored_level(&self, level: LogLevel) -> ColoredString {
+        match level {
+            LogLevel::Debug => "DEBUG".cyan(),
+            LogLevel::Info => "INFO".green(),
+            LogLevel::Warning => "WARNING".yellow(),
+            LogLevel::Error => "ERROR".red(),
+            LogLevel::Critical => "CRITICAL".red().bold(),
+        }
+    }
+}
+
+impl Default for DefaultFormatter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LogFormatter for DefaultFormatter {
+    fn format(&self, level: LogLevel, message: &str, context: Option<&LogContext>) -> String {
+        // Форматируем время
+        let formatted_time = Local::now().format(&self.time_format).to_string();
+
+        let context = match context {
+            Some(context) => context,
+            None => {
+                return format!(
+                    "{} [{}] {}",
+                    formatted_time,
+                    self.get_colored_level(level),
+                    message
+                )
+            }
+        };
+
+        // Добавляем информацию о местоположении, если есть
+        let location = if let (Some(file), Some(line)) = (&context.file, context.line) {
+            format!(" ({}: {})", file, line)
+        } else {
+            String::new()
+        };
+
+        // Добавляем вызывающего, если есть
+        let caller = if let Some(caller) = &context.caller {
+            format!(" [{}]", caller)
+        } else {
+            String::new()
+        };
+
+        format!(
+            "{} [{}]{}{} {}",
+            formatted_time,
+            self.get_colored_level(level),
+            location,
+            caller,
+            message
+        )
+    }
+}
+
+/// Форматировщик JSON-строк в формате `FileLogger`
+///
+/// Каждая строка содержит поля `timestamp`, `local_time`, `level`, `message`
+/// и поля контекста, если он передан.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonFormatter;
+
+impl JsonFormatter {
+    /// Создает форматировщик JSON-строк
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl LogFormatter for JsonFormatter {
+    fn format(&self, level: LogLevel, message: &str, context: Option<&LogContext>) -> String {
+        json_entry(level, message, context).to_string()
+    }
+}
@@ -1,9 +1,23 @@
+pub mod async_file_logger;
 pub mod console_logger;
+pub mod dedup_logger;
 pub mod file_logger;
+pub mod filtering_logger;
+pub mod formatter;
+pub mod null_logger;
+pub mod redacting_logger;
 pub mod strategies;
+pub mod tracing_logger;
 pub mod traits;
 
-pub use console_logger::ConsoleLogger;
+pub use async_file_logger::AsyncFileLogger;
+pub use console_logger::{ConsoleLogger, ConsoleTarget};
+pub use dedup_logger::DedupLogger;
 pub use file_logger::FileLogger;
+pub use filtering_logger::{FilteringLogger, LogFilter};
+pub use formatter::{DefaultFormatter, JsonFormatter, LogFormatter};
+pub use null_logger::NullLogger;
+pub use redacting_logger::RedactingLogger;
 pub use strategies::CompositeLogger;
-pub use traits::{LogContext, LogLevel, Logger, LoggingStrategy};
+pub use tracing_logger::TracingLogger;
+pub use traits::{LogContext, LogLevel, Logger, LoggingStrategy, OutputStream};
@@ -0,0 +1,23 @@
+use crate::logging::traits::{LogContext, LogLevel, Logger, OutputStream};
+
+/// Логгер, игнорирующий все сообщения
+///
+/// Позволяет подключить логгер там, где логирование не нужно (например, в
+/// бенчмарках), не меняя путь выполнения, как при отсутствии логгера.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NullLogger;
+
+impl NullLogger {
+    /// Создает новый пустой логгер
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Logger for NullLogger {
+    fn log(&self, _level: LogLevel, _message: &str) {}
+
+    fn log_with_context(&self, _level: LogLevel, _message: &str, _context: &LogContext) {}
+
+    fn log_output(&self, _stream: OutputStream, _line: &str) {}
+}
@@ -0,0 +1,129 @@
+use std::borrow::Cow;
+
+use regex::{Captures, Regex};
+use serde_json::Value;
+
+use crate::logging::traits::{LogContext, LogLevel, Logger, OutputStream};
+
+/// Значение, которым заменяются найденные секреты
+const REDACTED_VALUE: &str = "***";
+
+/// Логгер-обертка, скрывающий секреты в сообщениях перед передачей внутреннему логгеру
+///
+/// Секреты скрываются также в контексте сообщения: в имени вызывающего метода,
+/// имени файла и строковых значениях дополнительных данных.
+///
+/// Если шаблон содержит группы, заменяется только их содержимое (например,
+/// `password=(\S+)` превращает `password=hunter2` в `password=***`), иначе
+/// заменяется все совпадение.
+pub struct RedactingLogger {
+    /// Логгер, получающий сообщения со скрытыми секретами
+    inner: Box<dyn Logger>,
+
+    /// Шаблоны секретов
+    patterns: Vec<Regex>,
+}
+
+impl RedactingLogger {
+    /// Создает логгер со списком шаблонов секретов
+    pub fn new(inner: Box<dyn Logger>, patterns: Vec<Regex>) -> Self {
+        Self { inner, patterns }
+    }
+
+    /// Добавляет шаблон секрета
+    pub fn with_pattern(mut self, pattern: Regex) -> Self {
+        self.patterns.push(pattern);
+        self
+    }
+
+    /// Заменяет все найденные секреты в сообщении
+    fn redact<'a>(&self, message: &'a str) -> Cow<'a, str> {
+        let mut message = Cow::Borrowed(message);
+
+        for pattern in &self.patterns {
+            if !pattern.is_match(&message) {
+                continue;
+            }
+
+            let redacted = pattern
+                .replace_all(&message, |caps: &Captures| Self::redact_match(caps))
+                .into_owned();
+            message = Cow::Owned(redacted);
+        }
+
+        message
+    }
+
+    /// Скрывает секреты в строковых полях контекста и строковых значениях `extra`
+    fn redact_context(&self, context: &LogContext) -> LogContext {
+        let mut context = context.clone();
+
+        for field in [&mut context.caller, &mut context.file]
+            .into_iter()
+            .flatten()
+        {
+            *field = self.redact(field).into_owned();
+        }
+
+        if let Some(extra) = &mut context.extra {
+            self.redact_value(extra);
+        }
+
+        context
+    }
+
+    /// Рекурсивно скрывает секреты в строковых значениях JSON
+    fn redact_value(&self, value: &mut Value) {
+        match value {
+            Value::String(text) => *text = self.redact(text).into_owned(),
+            Value::Array(items) => items.iter_mut().for_each(|item| self.redact_value(item)),
+            Value::Object(map) => map.values_mut().for_each(|item| self.redact_value(item)),
+            _ => {}
+        }
+    }
+
+    /// Формирует замену совпадения, скрывая группы (или все совпадение, если групп нет)
+    fn redact_match(caps: &Captures) -> String {
+        if caps.len() == 1 {
+            return REDACTED_VALUE.to_string();
+        }
+
+        let whole = &caps[0];
+        let offset = caps.get(0).map_or(0, |m| m.start());
+
+        // Позиция в совпадении, до которой текст уже обработан
+        let mut position = 0;
+        let mut replaced = String::new();
+
+        for group in caps.iter().skip(1).flatten() {
+            let start = group.start() - offset;
+
+            // Вложенные группы уже скрыты вместе с внешней
+            if start < position {
+                continue;
+            }
+
+            replaced.push_str(&whole[position..start]);
+            replaced.push_str(REDACTED_VALUE);
+            position = group.end() - offset;
+        }
+
+        replaced.push_str(&whole[position..]);
+        replaced
+    }
+}
+
+impl Logger for RedactingLogger {
+    fn log(&self, level: LogLevel, message: &str) {
+        self.inner.log(level, &self.redact(message));
+    }
+
+    fn log_with_context(&self, level: LogLevel, message: &str, context: &LogContext) {
+        self.inner
+            .log_with_context(level, &self.redact(message), &self.redact_context(context));
+    }
+
+    fn log_output(&self, stream: OutputStream, line: &str) {
+        self.inner.log_output(stream, &self.redact(line));
+    }
+}
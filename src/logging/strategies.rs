@@ -1,6 +1,6 @@
 use std::sync::Mutex;
 
-use crate::logging::traits::{LogContext, LogLevel, Logger, LoggingStrategy};
+use crate::logging::traits::{LogContext, LogLevel, Logger, LoggingStrategy, OutputStream};
 
 /// Композитный логгер, объединяющий несколько стратегий логирования
 pub struct CompositeLogger {
@@ -24,6 +24,12 @@ impl CompositeLogger {
     }
 }
 
+impl Default for CompositeLogger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Logger for CompositeLogger {
     fn log(&self, level: LogLevel, message: &str) {
         // Получаем блокировку логгеров
@@ -44,6 +50,16 @@ impl Logger for CompositeLogger {
             }
         }
     }
+
+    fn log_output(&self, stream: OutputStream, line: &str) {
+        // Получаем блокировку логгеров
+        if let Ok(loggers) = self.loggers.lock() {
+            // Отправляем строку вывода во все логгеры
+            for logger in loggers.iter() {
+                logger.log_output(stream, line);
+            }
+        }
+    }
 }
 
 impl LoggingStrategy for CompositeLogger {
@@ -0,0 +1,66 @@
+use tracing::Level;
+
+use crate::logging::traits::{LogContext, LogLevel, Logger};
+
+/// Отправляет событие `tracing` с уровнем, соответствующим уровню логирования
+macro_rules! emit_event {
+    ($level:expr, $($fields:tt)*) => {
+        match $level {
+            LogLevel::Debug => tracing::event!(Level::DEBUG, $($fields)*),
+            LogLevel::Info => tracing::event!(Level::INFO, $($fields)*),
+            LogLevel::Warning => tracing::event!(Level::WARN, $($fields)*),
+            LogLevel::Error => tracing::event!(Level::ERROR, $($fields)*),
+            LogLevel::Critical => tracing::event!(Level::ERROR, critical = true, $($fields)*),
+        }
+    };
+}
+
+/// Логгер, передающий сообщения в экосистему `tracing`
+///
+/// Сообщения отправляются как события текущего подписчика `tracing`,
+/// а поля `LogContext` передаются как структурированные поля события.
+/// Уровень `Critical` отображается в `ERROR` с полем `critical = true`.
+#[derive(Debug, Clone, Copy)]
+pub struct TracingLogger {
+    /// Минимальный уровень логирования
+    min_level: LogLevel,
+}
+
+impl TracingLogger {
+    /// Создает новый логгер для `tracing`
+    pub fn new(min_level: LogLevel) -> Self {
+        Self { min_level }
+    }
+}
+
+impl Default for TracingLogger {
+    fn default() -> Self {
+        Self::new(LogLevel::Debug)
+    }
+}
+
+impl Logger for TracingLogger {
+    fn log(&self, level: LogLevel, message: &str) {
+        // Проверяем, нужно ли логировать это сообщение
+        if level as u8 >= self.min_level as u8 {
+            emit_event!(level, "{}", message);
+        }
+    }
+
+    fn log_with_context(&self, level: LogLevel, message: &str, context: &LogContext) {
+        // Проверяем, нужно ли логировать это сообщение
+        if level as u8 >= self.min_level as u8 {
+            let extra = context.extra.as_ref().map(tracing::field::display);
+
+            emit_event!(
+                level,
+                caller = context.caller.as_deref(),
+                file = context.file.as_deref(),
+                line = context.line,
+                extra,
+                "{}",
+                message
+            );
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::sync::Arc;
 
 /// Уровни логирования
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Hash)]
@@ -36,6 +37,15 @@ impl fmt::Display for LogLevel {
     }
 }
 
+/// Поток вывода команды
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Hash)]
+pub enum OutputStream {
+    /// Стандартный вывод
+    Stdout,
+    /// Стандартный поток ошибок
+    Stderr,
+}
+
 /// Контекст для логирования
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LogContext {
@@ -122,6 +132,47 @@ pub trait Logger: Send + Sync {
     fn critical(&self, message: &str) {
         self.log(LogLevel::Critical, message);
     }
+
+    /// Логирует строку вывода команды из указанного потока
+    fn log_output(&self, stream: OutputStream, line: &str) {
+        match stream {
+            OutputStream::Stdout => self.log(LogLevel::Info, line),
+            OutputStream::Stderr => self.log(LogLevel::Warning, line),
+        }
+    }
+}
+
+/// Упакованный логгер передает сообщения логгеру, на который указывает
+impl<T: Logger + ?Sized> Logger for Box<T> {
+    fn log(&self, level: LogLevel, message: &str) {
+        (**self).log(level, message);
+    }
+
+    fn log_with_context(&self, level: LogLevel, message: &str, context: &LogContext) {
+        (**self).log_with_context(level, message, context);
+    }
+
+    fn log_output(&self, stream: OutputStream, line: &str) {
+        (**self).log_output(stream, line);
+    }
+}
+
+/// Общий логгер передает сообщения логгеру, на который указывает
+///
+/// Позволяет использовать один логгер в нескольких цепочках:
+/// `chain.with_logger(Box::new(Arc::clone(&logger)))`.
+impl<T: Logger + ?Sized> Logger for Arc<T> {
+    fn log(&self, level: LogLevel, message: &str) {
+        (**self).log(level, message);
+    }
+
+    fn log_with_context(&self, level: LogLevel, message: &str, context: &LogContext) {
+        (**self).log_with_context(level, message, context);
+    }
+
+    fn log_output(&self, stream: OutputStream, line: &str) {
+        (**self).log_output(stream, line);
+    }
 }
 
 /// Трейт стратегии логирования (паттерн Стратегия)
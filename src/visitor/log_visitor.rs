@@ -8,7 +8,7 @@ use crate::CommandExecution;
 /// Структура для логирования команд
 pub struct LogVisitor<'a> {
     /// Логгер для записи событий
-    logger: &'a Box<dyn Logger>,
+    logger: &'a dyn Logger,
 
     /// Уровень логирования
     level: LogLevel,
@@ -16,7 +16,7 @@ pub struct LogVisitor<'a> {
 
 impl<'a> LogVisitor<'a> {
     /// Создает новый экземпляр LogVisitor
-    pub fn new(logger: &'a Box<dyn Logger>, level: LogLevel) -> Self {
+    pub fn new(logger: &'a dyn Logger, level: LogLevel) -> Self {
         Self { logger, level }
     }
 
@@ -26,14 +26,26 @@ impl<'a> LogVisitor<'a> {
     }
 
     /// Устанавливает логгер
-    pub fn set_logger(&mut self, logger: &'a Box<dyn Logger>) {
+    pub fn set_logger(&mut self, logger: &'a dyn Logger) {
         self.logger = logger;
     }
 }
 
 impl<'a> Visitor for LogVisitor<'a> {
     fn visit_shell_command(&mut self, command: &ShellCommand) {
-        let message = format!("Команда: {}", command.name());
+        let mut message = format!("Команда: {}", command.name());
+
+        // Метки выводятся в отсортированном порядке для стабильности логов
+        if !command.tags().is_empty() {
+            let mut tags: Vec<_> = command
+                .tags()
+                .iter()
+                .map(|(key, value)| format!("{}={}", key, value))
+                .collect();
+            tags.sort();
+            message.push_str(&format!(" [{}]", tags.join(", ")));
+        }
+
         self.logger.log(self.level, &message);
     }
 
@@ -49,6 +61,11 @@ impl<'a> Visitor for LogVisitor<'a> {
 
         self.logger.log(self.level, &message);
     }
+
+    fn visit_generic_command(&mut self, name: &str, kind: &str) {
+        let message = format!("Команда: {} (вид: {})", name, kind);
+        self.logger.log(self.level, &message);
+    }
 }
 
 impl<'a> fmt::Debug for LogVisitor<'a> {
@@ -0,0 +1,85 @@
+use std::collections::HashSet;
+
+use crate::command::{CompositeCommand, ExecutionMode, ShellCommand};
+use crate::visitor::Visitor;
+use crate::CommandExecution;
+
+/// Посетитель, собирающий статистику по командам до их выполнения
+#[derive(Debug, Clone, Default)]
+pub struct MetricsVisitor {
+    /// Количество shell команд
+    shell_commands: usize,
+
+    /// Количество составных команд
+    composite_commands: usize,
+
+    /// Количество пользовательских команд
+    generic_commands: usize,
+
+    /// Количество shell команд с поддержкой отката
+    rollback_commands: usize,
+
+    /// Встреченные режимы выполнения
+    execution_modes: HashSet<ExecutionMode>,
+}
+
+impl MetricsVisitor {
+    /// Создает новый посетитель со сброшенной статистикой
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Возвращает количество shell команд
+    pub fn shell_commands(&self) -> usize {
+        self.shell_commands
+    }
+
+    /// Возвращает количество составных команд
+    pub fn composite_commands(&self) -> usize {
+        self.composite_commands
+    }
+
+    /// Возвращает количество пользовательских команд
+    pub fn generic_commands(&self) -> usize {
+        self.generic_commands
+    }
+
+    /// Возвращает общее количество посещенных команд
+    pub fn total_commands(&self) -> usize {
+        self.shell_commands + self.composite_commands + self.generic_commands
+    }
+
+    /// Возвращает количество shell команд с поддержкой отката
+    ///
+    /// Составные команды не учитываются, так как их откат
+    /// определяется вложенными командами.
+    pub fn rollback_commands(&self) -> usize {
+        self.rollback_commands
+    }
+
+    /// Возвращает множество встреченных режимов выполнения
+    pub fn execution_modes(&self) -> &HashSet<ExecutionMode> {
+        &self.execution_modes
+    }
+}
+
+impl Visitor for MetricsVisitor {
+    fn visit_shell_command(&mut self, command: &ShellCommand) {
+        self.shell_commands += 1;
+
+        if command.supports_rollback() {
+            self.rollback_commands += 1;
+        }
+
+        self.execution_modes.insert(command.execution_mode());
+    }
+
+    fn visit_composite_command(&mut self, command: &CompositeCommand) {
+        self.composite_commands += 1;
+        self.execution_modes.insert(command.execution_mode());
+    }
+
+    fn visit_generic_command(&mut self, _name: &str, _kind: &str) {
+        self.generic_commands += 1;
+    }
+}
@@ -1,5 +1,9 @@
 pub mod log_visitor;
+pub mod metrics_visitor;
 pub mod traits;
+pub mod validation_visitor;
 
 pub use log_visitor::LogVisitor;
+pub use metrics_visitor::MetricsVisitor;
 pub use traits::Visitor;
+pub use validation_visitor::{ValidationRule, ValidationVisitor, ValidationWarning};
@@ -5,4 +5,13 @@ pub trait Visitor {
 
     /// Посещает составную команду
     fn visit_composite_command(&mut self, command: &crate::command::CompositeCommand);
+
+    /// Посещает условную команду (вызывается перед посещением оборачиваемой команды)
+    fn visit_conditional_command(&mut self, _command: &crate::command::ConditionalCommand) {}
+
+    /// Посещает команду вложенной цепочки (вызывается перед посещением команд цепочки)
+    fn visit_chain_command(&mut self, _command: &crate::chain::ChainCommand) {}
+
+    /// Посещает пользовательскую команду, для которой нет отдельного метода посетителя
+    fn visit_generic_command(&mut self, _name: &str, _kind: &str) {}
 }
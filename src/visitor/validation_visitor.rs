@@ -0,0 +1,169 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::fmt;
+
+use crate::command::{CompositeCommand, ShellCommand};
+use crate::visitor::Visitor;
+use crate::CommandExecution;
+
+lazy_static! {
+    static ref RECURSIVE_REMOVE_PATTERN: Regex =
+        Regex::new(r"(?:^|[\s;&|(])rm\s+((?:-{1,2}[\w-]+\s+)+)(?:/\*?|~/?|\$HOME/?)(?:$|[\s;&|)])")
+            .unwrap();
+    static ref SUBSTITUTION_PATTERN: Regex = Regex::new(r"(?:^|[^\\])(?:\$\(|`)").unwrap();
+}
+
+/// Правило проверки команды
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ValidationRule {
+    /// Пустая командная строка
+    EmptyCommand,
+    /// Рекурсивное удаление корня файловой системы или домашнего каталога
+    DestructiveRemove,
+    /// Неэкранированная подстановка команды `$(...)` или обратные кавычки
+    CommandSubstitution,
+}
+
+/// Предупреждение, найденное при проверке команды
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationWarning {
+    /// Имя команды
+    pub command_name: String,
+
+    /// Нарушенное правило
+    pub rule: ValidationRule,
+
+    /// Описание проблемы
+    pub message: String,
+}
+
+impl fmt::Display for ValidationWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.command_name, self.message)
+    }
+}
+
+/// Посетитель, проверяющий командные строки на опасные конструкции до выполнения
+///
+/// Посетитель только накапливает предупреждения; решение о запуске
+/// команд принимает вызывающий код.
+#[derive(Debug, Clone)]
+pub struct ValidationVisitor {
+    /// Проверять ли пустые команды
+    check_empty: bool,
+
+    /// Проверять ли рекурсивное удаление корня или домашнего каталога
+    check_destructive: bool,
+
+    /// Разрешена ли подстановка команд
+    allow_substitution: bool,
+
+    /// Найденные предупреждения
+    warnings: Vec<ValidationWarning>,
+}
+
+impl ValidationVisitor {
+    /// Создает посетитель с проверками по умолчанию
+    ///
+    /// По умолчанию проверяются пустые команды и опасное удаление,
+    /// подстановка команд разрешена.
+    pub fn new() -> Self {
+        Self {
+            check_empty: true,
+            check_destructive: true,
+            allow_substitution: true,
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Включает или отключает проверку пустых команд
+    pub fn with_check_empty(mut self, enabled: bool) -> Self {
+        self.check_empty = enabled;
+        self
+    }
+
+    /// Включает или отключает проверку опасного удаления
+    pub fn with_check_destructive(mut self, enabled: bool) -> Self {
+        self.check_destructive = enabled;
+        self
+    }
+
+    /// Разрешает или запрещает подстановку команд `$(...)` и обратные кавычки
+    pub fn with_allow_substitution(mut self, allowed: bool) -> Self {
+        self.allow_substitution = allowed;
+        self
+    }
+
+    /// Возвращает найденные предупреждения
+    pub fn warnings(&self) -> &[ValidationWarning] {
+        &self.warnings
+    }
+
+    /// Возвращает найденные предупреждения, потребляя посетитель
+    pub fn into_warnings(self) -> Vec<ValidationWarning> {
+        self.warnings
+    }
+
+    /// Проверяет, что предупреждений не найдено
+    pub fn is_valid(&self) -> bool {
+        self.warnings.is_empty()
+    }
+
+    /// Добавляет предупреждение
+    fn warn(&mut self, command_name: &str, rule: ValidationRule, message: &str) {
+        self.warnings.push(ValidationWarning {
+            command_name: command_name.to_string(),
+            rule,
+            message: message.to_string(),
+        });
+    }
+
+    /// Проверяет, содержит ли командная строка рекурсивное удаление корня
+    fn is_destructive_remove(command: &str) -> bool {
+        RECURSIVE_REMOVE_PATTERN.captures_iter(command).any(|cap| {
+            cap[1].split_whitespace().any(|flag| {
+                flag == "--recursive"
+                    || (!flag.starts_with("--") && (flag.contains('r') || flag.contains('R')))
+            })
+        })
+    }
+}
+
+impl Default for ValidationVisitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Visitor for ValidationVisitor {
+    fn visit_shell_command(&mut self, command: &ShellCommand) {
+        let command_line = command.command();
+
+        if self.check_empty && command_line.trim().is_empty() {
+            self.warn(
+                command.name(),
+                ValidationRule::EmptyCommand,
+                "пустая команда",
+            );
+            return;
+        }
+
+        if self.check_destructive && Self::is_destructive_remove(command_line) {
+            self.warn(
+                command.name(),
+                ValidationRule::DestructiveRemove,
+                "рекурсивное удаление корня файловой системы или домашнего каталога",
+            );
+        }
+
+        if !self.allow_substitution && SUBSTITUTION_PATTERN.is_match(command_line) {
+            self.warn(
+                command.name(),
+                ValidationRule::CommandSubstitution,
+                "неэкранированная подстановка команды",
+            );
+        }
+    }
+
+    fn visit_composite_command(&mut self, _command: &CompositeCommand) {}
+}
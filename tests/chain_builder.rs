@@ -0,0 +1,54 @@
+use command_system::command::ShellCommand;
+use command_system::{BuildError, ChainBuilder, ChainExecutionMode};
+
+#[test]
+fn empty_chain_name_is_rejected() {
+    let result = ChainBuilder::new("")
+        .command(ShellCommand::new("step", "true"))
+        .try_build();
+
+    assert!(matches!(result, Err(BuildError::EmptyName)));
+}
+
+#[tokio::test]
+async fn valid_chain_is_built() {
+    let chain = ChainBuilder::new("deploy")
+        .command(ShellCommand::new("first", "true"))
+        .command(ShellCommand::new("second", "true"))
+        .try_build()
+        .unwrap();
+
+    assert_eq!(chain.name(), "deploy");
+    let result = chain.execute().await.unwrap();
+    assert!(result.success);
+    assert_eq!(result.results.len(), 2);
+}
+
+#[tokio::test]
+async fn parallel_chain_accepts_default_commands() {
+    let chain = ChainBuilder::new("checks")
+        .execution_mode(ChainExecutionMode::Parallel)
+        .build_with_commands(vec![
+            ShellCommand::new("lint", "true"),
+            ShellCommand::new("test", "true"),
+        ]);
+
+    let result = chain.execute().await.unwrap();
+    assert!(result.success);
+    assert_eq!(result.results.len(), 2);
+
+    let chain = ChainBuilder::new("checks")
+        .execution_mode(ChainExecutionMode::Parallel)
+        .command(ShellCommand::new("lint", "true"))
+        .try_build();
+    assert!(chain.is_ok());
+}
+
+#[test]
+fn build_does_not_validate_configuration() {
+    let chain = ChainBuilder::new("")
+        .command(ShellCommand::new("step", "true"))
+        .build();
+
+    assert_eq!(chain.name(), "");
+}
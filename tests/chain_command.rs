@@ -0,0 +1,116 @@
+mod common;
+
+use std::sync::Arc;
+
+use command_system::command::{CommandExecution, ExecutionContext, ShellCommand};
+use command_system::{ChainCommand, CommandChain, ConditionalCommand};
+
+use common::{read_lines, temp_path};
+
+#[tokio::test]
+async fn nested_rollback_failure_is_reported() {
+    let mut inner = CommandChain::new("inner");
+    inner.add_command(ShellCommand::new("step", "true").with_rollback("false"));
+
+    let mut outer = CommandChain::new("outer");
+    outer
+        .add_command(ChainCommand::new(Arc::new(inner)))
+        .add_command(ShellCommand::new("fail", "false"));
+
+    let result = outer.execute().await.unwrap();
+
+    assert!(!result.success);
+    let nested = result
+        .rollback_results
+        .iter()
+        .find(|r| r.command_name == "inner")
+        .expect("результат отката вложенной цепочки");
+    assert!(!nested.success);
+    assert!(nested.error.as_deref().unwrap().contains("step"));
+}
+
+#[tokio::test]
+async fn nested_rollback_success_is_reported() {
+    let mut inner = CommandChain::new("inner");
+    inner.add_command(ShellCommand::new("step", "true").with_rollback("true"));
+
+    let command = ChainCommand::new(Arc::new(inner));
+    assert!(command.execute().await.unwrap().success);
+
+    let rollback = command.rollback().await.unwrap();
+    assert!(rollback.success);
+}
+
+#[tokio::test]
+async fn inner_chain_runs_as_single_command() {
+    let mut inner = CommandChain::new("inner");
+    inner
+        .add_command(ShellCommand::new("first", "echo one"))
+        .add_command(ShellCommand::new("second", "echo two"));
+
+    let mut outer = CommandChain::new("outer");
+    outer
+        .add_command(ShellCommand::new("before", "echo start"))
+        .add_command(ChainCommand::new(Arc::new(inner)));
+
+    let result = outer.execute().await.unwrap();
+
+    assert!(result.success);
+    assert_eq!(result.results.len(), 2);
+    let nested = &result.results[1];
+    assert_eq!(nested.command_name, "inner");
+    assert!(nested.success);
+    assert!(nested.output.contains("first:\none"));
+    assert!(nested.output.contains("second:\ntwo"));
+    assert_eq!(nested.data.as_ref().unwrap().as_array().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn rollback_uses_commands_of_given_run() {
+    let marker = temp_path("nested_marker");
+    let log = temp_path("nested_rollback");
+
+    let mut inner = CommandChain::new("inner");
+    inner
+        .add_command(
+            ShellCommand::new("first", "true")
+                .with_rollback(&format!("echo undo-first >> {}", log.display())),
+        )
+        .add_command(ConditionalCommand::if_path_exists(
+            ShellCommand::new("second", "true")
+                .with_rollback(&format!("echo undo-second >> {}", log.display())),
+            &marker,
+        ));
+    let command = Arc::new(ChainCommand::new(Arc::new(inner)));
+
+    // Во втором запуске выполняются обе команды, в первом - только первая
+    let first_run = command.execute().await.unwrap();
+    std::fs::write(&marker, "").unwrap();
+    let second_run = command.execute().await.unwrap();
+
+    let context = ExecutionContext::new().with_forward_result(&first_run);
+    assert!(
+        command
+            .rollback_with_context(&context)
+            .await
+            .unwrap()
+            .success
+    );
+    assert_eq!(read_lines(&log), vec!["undo-first"]);
+
+    let context = ExecutionContext::new().with_forward_result(&second_run);
+    assert!(
+        command
+            .rollback_with_context(&context)
+            .await
+            .unwrap()
+            .success
+    );
+    assert_eq!(
+        read_lines(&log),
+        vec!["undo-first", "undo-second", "undo-first"]
+    );
+
+    let _ = std::fs::remove_file(&marker);
+    let _ = std::fs::remove_file(&log);
+}
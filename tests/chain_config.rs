@@ -0,0 +1,40 @@
+mod common;
+
+use command_system::chain::ChainConfig;
+use command_system::{ChainExecutionMode, CommandChain};
+
+use common::temp_path;
+
+const CHAIN_YAML: &str = r#"
+name: deploy
+mode: Sequential
+commands:
+  - name: greet
+    command: echo hello
+  - name: stage
+    command: sh -c 'echo $STAGE'
+    env:
+      STAGE: prod
+"#;
+
+#[tokio::test]
+async fn yaml_chain_round_trips_and_executes() {
+    let config = ChainConfig::from_yaml(CHAIN_YAML).unwrap();
+    assert_eq!(config.mode, ChainExecutionMode::Sequential);
+    assert!(config.rollback_on_error);
+    assert_eq!(config.commands.len(), 2);
+
+    let yaml = serde_yaml::to_string(&config).unwrap();
+    assert_eq!(ChainConfig::from_yaml(&yaml).unwrap(), config);
+
+    let path = temp_path("chain").with_extension("yaml");
+    std::fs::write(&path, yaml).unwrap();
+    let chain = CommandChain::from_config_file(&path.display().to_string()).unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    let result = chain.execute().await.unwrap();
+
+    assert!(result.success);
+    let outputs: Vec<_> = result.results.iter().map(|r| r.output.trim()).collect();
+    assert_eq!(outputs, vec!["hello", "prod"]);
+}
@@ -0,0 +1,74 @@
+use std::time::Duration;
+
+use command_system::command::CommandExecution;
+use command_system::command::ShellCommand;
+use command_system::{BuildError, CommandBuilder};
+
+#[test]
+fn for_each_expands_template_over_hosts() {
+    let hosts = vec!["web1".to_string(), "web2".to_string(), "db1".to_string()];
+
+    let commands = CommandBuilder::new("ping", "ping -c 1 {host}")
+        .rollback("echo undo {host}")
+        .for_each(hosts, "host");
+
+    let names: Vec<_> = commands.iter().map(|c| c.name()).collect();
+    let lines: Vec<_> = commands.iter().map(|c| c.command()).collect();
+
+    assert_eq!(names, vec!["ping_web1", "ping_web2", "ping_db1"]);
+    assert_eq!(
+        lines,
+        vec!["ping -c 1 web1", "ping -c 1 web2", "ping -c 1 db1"]
+    );
+    assert_eq!(commands[2].rollback_command(), Some("echo undo db1"));
+}
+
+#[test]
+fn accessors_return_builder_values() {
+    let command = CommandBuilder::new("deploy", "make deploy")
+        .working_dir("/srv/app")
+        .timeout_duration(Duration::from_millis(1500))
+        .env_var("STAGE", "prod")
+        .rollback("make undeploy")
+        .build();
+
+    assert_eq!(command.name(), "deploy");
+    assert_eq!(command.command(), "make deploy");
+    assert_eq!(command.working_dir(), Some("/srv/app"));
+    assert_eq!(command.timeout(), Some(Duration::from_millis(1500)));
+    assert_eq!(
+        command.env_vars().get("STAGE").map(String::as_str),
+        Some("prod")
+    );
+    assert_eq!(command.rollback_command(), Some("make undeploy"));
+}
+
+#[test]
+fn unbalanced_brace_is_rejected() {
+    let error = CommandBuilder::new("greet", "echo {name")
+        .try_build()
+        .unwrap_err();
+
+    match error {
+        BuildError::InvalidCommand(message) => {
+            assert!(message.contains("незакрытая фигурная скобка в позиции 6"))
+        }
+        other => panic!("unexpected error: {:?}", other),
+    }
+    assert!(ShellCommand::new("greet", "echo name}").validate().is_err());
+}
+
+#[test]
+fn empty_placeholder_is_rejected() {
+    let error = CommandBuilder::new("greet", "echo {}")
+        .try_build()
+        .unwrap_err();
+
+    match error {
+        BuildError::InvalidCommand(message) => {
+            assert!(message.contains("пустая подстановка в позиции 6"))
+        }
+        other => panic!("unexpected error: {:?}", other),
+    }
+    assert!(ShellCommand::new("greet", "echo {name}").validate().is_ok());
+}
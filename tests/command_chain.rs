@@ -0,0 +1,858 @@
+mod common;
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use command_system::command::traits::CommandError;
+use command_system::command::{Command, ExecutionMode, ShellCommand};
+use command_system::{
+    ChainEvent, ChainExecutionMode, ChainResult, CommandChain, LogLevel, NullLogger,
+};
+use futures::StreamExt;
+use tokio_util::sync::CancellationToken;
+
+use common::{read_lines, temp_path, MemoryLogger};
+
+#[tokio::test]
+async fn chain_timeout_does_not_interrupt_rollback() {
+    let log = temp_path("timeout_rollback");
+
+    let mut chain = CommandChain::new("chain");
+    chain
+        .with_chain_timeout(Duration::from_millis(200))
+        .add_command(
+            ShellCommand::new("first", "true")
+                .with_rollback(&format!("sleep 0.5 && echo first >> {}", log.display())),
+        )
+        .add_command(ShellCommand::new("second", "false"));
+
+    let result = chain.execute().await.unwrap();
+
+    assert!(!result.success);
+    assert!(result.rolled_back);
+    assert_eq!(read_lines(&log), vec!["first".to_string()]);
+    let _ = std::fs::remove_file(&log);
+}
+
+#[tokio::test]
+async fn cancellation_does_not_interrupt_rollback() {
+    let log = temp_path("cancel_rollback");
+
+    let mut chain = CommandChain::new("chain");
+    chain
+        .add_command(
+            ShellCommand::new("first", "true")
+                .with_rollback(&format!("sleep 0.5 && echo first >> {}", log.display())),
+        )
+        .add_command(ShellCommand::new("second", "false"));
+
+    let token = CancellationToken::new();
+    let canceller = token.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        canceller.cancel();
+    });
+
+    let result = chain.execute_cancellable(token).await.unwrap();
+
+    assert!(!result.success);
+    assert_eq!(read_lines(&log), vec!["first".to_string()]);
+    let _ = std::fs::remove_file(&log);
+}
+
+#[tokio::test]
+async fn rollback_retries_are_logged_by_chain_logger() {
+    let logger = MemoryLogger::new();
+
+    let mut chain = CommandChain::new("chain");
+    chain
+        .with_logger(logger.boxed())
+        .add_command(
+            ShellCommand::new("first", "true")
+                .with_rollback("false")
+                .with_rollback_retries(2),
+        )
+        .add_command(ShellCommand::new("second", "false"));
+
+    let result = chain.execute().await.unwrap();
+
+    assert!(!result.success);
+    assert!(logger.contains("повторная попытка 1 из 2"));
+    assert!(logger.contains("повторная попытка 2 из 2"));
+}
+
+#[tokio::test]
+async fn rollback_processes_are_reported_in_pids() {
+    let mut chain = CommandChain::new("chain");
+    chain
+        .add_command(ShellCommand::new("first", "true").with_rollback("true"))
+        .add_command(ShellCommand::new("second", "false"));
+
+    let result = chain.execute().await.unwrap();
+
+    assert!(!result.success);
+    // Два прямых процесса и процесс отката
+    assert_eq!(result.pids.len(), 3);
+}
+
+#[tokio::test]
+async fn rollback_output_placeholder_is_not_reparsed() {
+    let log = temp_path("output_braces");
+
+    let mut chain = CommandChain::new("chain");
+    chain
+        .add_command(
+            // Выводит `{x}`, не используя фигурные скобки в командной строке
+            ShellCommand::new("create", r"printf '\173x\175'")
+                .with_rollback(&format!("echo '{{__output__}}' >> {}", log.display())),
+        )
+        .add_command(ShellCommand::new("fail", "false"));
+
+    let result = chain.execute().await.unwrap();
+
+    assert!(!result.success);
+    assert_eq!(read_lines(&log), vec!["{x}".to_string()]);
+    let _ = std::fs::remove_file(&log);
+}
+
+#[tokio::test]
+async fn captured_value_is_not_reparsed() {
+    let mut chain = CommandChain::new("chain");
+    chain
+        .add_command(ShellCommand::new("produce", r"printf '\173y\175'").with_capture_as("id"))
+        .add_command(ShellCommand::new("consume", "echo '{id}'"));
+
+    let result = chain.execute().await.unwrap();
+
+    assert!(result.success);
+    assert_eq!(result.results[1].output.trim(), "{y}");
+}
+
+#[tokio::test]
+async fn override_beats_variables_file() {
+    let vars = temp_path("override_vars");
+    std::fs::write(&vars, r#"{"name": "from-file"}"#).unwrap();
+
+    let mut chain = CommandChain::new("chain");
+    chain
+        .with_overrides(HashMap::from([(
+            "name".to_string(),
+            "from-override".to_string(),
+        )]))
+        .add_command(
+            ShellCommand::new("show", "echo {#name}")
+                .with_variables_file(&vars.display().to_string()),
+        );
+
+    let result = chain.execute().await.unwrap();
+
+    assert!(result.success);
+    assert_eq!(result.results[0].output.trim(), "from-override");
+    let _ = std::fs::remove_file(&vars);
+}
+
+#[tokio::test]
+async fn overrides_do_not_enable_variable_sharing() {
+    let first_vars = temp_path("first_vars");
+    let second_vars = temp_path("second_vars");
+    std::fs::write(&first_vars, r#"{"x": "first"}"#).unwrap();
+    std::fs::write(&second_vars, r#"{"x": "second"}"#).unwrap();
+
+    let mut chain = CommandChain::new("chain");
+    chain
+        .with_overrides(HashMap::from([("other".to_string(), "1".to_string())]))
+        .add_command(
+            ShellCommand::new("first", "echo {#x}")
+                .with_variables_file(&first_vars.display().to_string()),
+        )
+        .add_command(
+            ShellCommand::new("second", "echo {#x}")
+                .with_variables_file(&second_vars.display().to_string()),
+        );
+
+    let result = chain.execute().await.unwrap();
+
+    assert!(result.success);
+    assert_eq!(result.results[1].output.trim(), "second");
+    let _ = std::fs::remove_file(&first_vars);
+    let _ = std::fs::remove_file(&second_vars);
+}
+
+#[tokio::test]
+async fn queued_time_excludes_previous_commands() {
+    let mut chain = CommandChain::new("chain");
+    chain
+        .add_command(ShellCommand::new("slow", "sleep 0.3"))
+        .add_command(ShellCommand::new("next", "true"));
+
+    let result = chain.execute().await.unwrap();
+
+    assert!(result.success);
+    assert!(result.results[1].queued_ms < 100);
+    assert!(result.results[0].exec_ms >= 300);
+}
+
+#[tokio::test]
+async fn parallel_chain_collects_all_errors() {
+    let mut chain = CommandChain::new("chain");
+    chain
+        .with_execution_mode(ChainExecutionMode::Parallel)
+        .with_rollback_on_error(false)
+        .add_command(ShellCommand::new("first", "echo first-broken 1>&2; exit 1"))
+        .add_command(ShellCommand::new("second", "true"))
+        .add_command(ShellCommand::new("third", "echo third-broken 1>&2; exit 2"));
+
+    let result = chain.execute().await.unwrap();
+
+    assert!(!result.success);
+    assert_eq!(result.errors.len(), 2);
+    assert!(result.errors.iter().any(|e| e.contains("first-broken")));
+    assert!(result.errors.iter().any(|e| e.contains("third-broken")));
+}
+
+#[tokio::test]
+async fn dry_run_spawns_no_processes() {
+    let marker = temp_path("dry_run");
+
+    let mut chain = CommandChain::new("chain");
+    chain
+        .with_dry_run(true)
+        .add_command(ShellCommand::new(
+            "touch",
+            &format!("touch {}", marker.display()),
+        ))
+        .add_command(ShellCommand::new("fail", "false"));
+
+    let result = chain.execute().await.unwrap();
+
+    assert!(result.success);
+    assert_eq!(result.results.len(), 2);
+    assert!(result
+        .results
+        .iter()
+        .all(|r| r.success && r.output.starts_with("[dry-run]")));
+    assert!(!marker.exists());
+}
+
+#[tokio::test]
+async fn rollback_skips_opted_out_command() {
+    let log = temp_path("skip_rollback");
+
+    let mut chain = CommandChain::new("chain");
+    chain
+        .with_rollback_on_error(true)
+        .add_command(
+            ShellCommand::new("first", "true")
+                .with_rollback(&format!("echo first >> {}", log.display())),
+        )
+        .add_command(
+            ShellCommand::new("second", "true")
+                .with_rollback(&format!("echo second >> {}", log.display()))
+                .with_skip_rollback(true),
+        )
+        .add_command(ShellCommand::new("third", "false"));
+
+    let result = chain.execute().await.unwrap();
+
+    assert!(!result.success);
+    assert!(result.rolled_back);
+    assert_eq!(read_lines(&log), vec!["first".to_string()]);
+    let _ = std::fs::remove_file(&log);
+}
+
+#[tokio::test]
+async fn events_follow_sequential_execution_order() {
+    let mut chain = CommandChain::new("chain");
+    chain
+        .add_command(ShellCommand::new("first", "true"))
+        .add_command(ShellCommand::new("second", "true"));
+
+    let (events, handle) = Arc::new(chain).execute_with_events();
+    let events: Vec<ChainEvent> = events.collect().await;
+    assert!(handle.await.unwrap().unwrap().success);
+
+    let order: Vec<String> = events
+        .iter()
+        .map(|event| match event {
+            ChainEvent::CommandStarted { name } => format!("start {}", name),
+            ChainEvent::CommandFinished { name, success, .. } => {
+                format!("finish {} {}", name, success)
+            }
+            ChainEvent::RollbackStarted => "rollback".to_string(),
+            ChainEvent::ChainFinished { success } => format!("chain {}", success),
+        })
+        .collect();
+
+    assert_eq!(
+        order,
+        vec![
+            "start first",
+            "finish first true",
+            "start second",
+            "finish second true",
+            "chain true",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn result_report_round_trips_through_json() {
+    let mut chain = CommandChain::new("chain");
+    chain
+        .with_rollback_on_error(false)
+        .add_command(ShellCommand::new("ok", "echo done"))
+        .add_command(ShellCommand::new("fail", "false"));
+
+    let result = chain.execute().await.unwrap();
+    let report = result.to_json();
+
+    let restored: ChainResult = serde_json::from_str(&report).unwrap();
+
+    assert_eq!(restored.success, result.success);
+    assert_eq!(restored.error, result.error);
+    assert_eq!(restored.pids, result.pids);
+    assert_eq!(restored.results.len(), 2);
+    assert_eq!(restored.results[0].output, "done\n");
+    assert_eq!(restored.results[1].status, result.results[1].status);
+    assert_eq!(restored.to_json(), report);
+}
+
+#[tokio::test]
+async fn chain_timeout_aborts_long_commands_promptly() {
+    let mut chain = CommandChain::new("chain");
+    chain
+        .with_chain_timeout(Duration::from_secs(1))
+        .add_command(ShellCommand::new("first", "sleep 5"))
+        .add_command(ShellCommand::new("second", "sleep 5"));
+
+    let started = Instant::now();
+    let result = chain.execute().await;
+
+    assert!(matches!(result, Err(CommandError::TimeoutError(_))));
+    assert!(started.elapsed() < Duration::from_secs(3));
+}
+
+#[tokio::test]
+async fn dag_runs_independent_branches_in_parallel() {
+    let mut chain = CommandChain::new("diamond");
+    chain
+        .with_execution_mode(ChainExecutionMode::Dag)
+        .add_command(ShellCommand::new("sink", "true").with_depends_on(&["left", "right"]))
+        .add_command(ShellCommand::new("left", "sleep 0.3").with_depends_on(&["source"]))
+        .add_command(ShellCommand::new("right", "sleep 0.3").with_depends_on(&["source"]))
+        .add_command(ShellCommand::new("source", "true"));
+
+    let result = chain.execute().await.unwrap();
+
+    assert!(result.success);
+    let find = |name: &str| {
+        result
+            .results
+            .iter()
+            .find(|r| r.command_name == name)
+            .unwrap()
+    };
+    let (source, left, right, sink) = (find("source"), find("left"), find("right"), find("sink"));
+
+    assert!(source.end_time <= left.start_time && source.end_time <= right.start_time);
+    assert!(left.start_time < right.end_time && right.start_time < left.end_time);
+    assert!(sink.start_time >= left.end_time && sink.start_time >= right.end_time);
+}
+
+#[tokio::test]
+async fn hooks_run_once_per_command() {
+    let before = Arc::new(AtomicUsize::new(0));
+    let after = Arc::new(AtomicUsize::new(0));
+    let (before_count, after_count) = (Arc::clone(&before), Arc::clone(&after));
+
+    let mut chain = CommandChain::new("chain");
+    chain
+        .with_before_each(Box::new(move |_| {
+            before_count.fetch_add(1, Ordering::SeqCst);
+        }))
+        .with_after_each(Box::new(move |_, _| {
+            after_count.fetch_add(1, Ordering::SeqCst);
+        }))
+        .add_command(ShellCommand::new("first", "true"))
+        .add_command(ShellCommand::new("second", "true"))
+        .add_command(ShellCommand::new("third", "true"));
+
+    assert!(chain.execute().await.unwrap().success);
+
+    assert_eq!(before.load(Ordering::SeqCst), 3);
+    assert_eq!(after.load(Ordering::SeqCst), 3);
+}
+
+#[test]
+fn rollback_preview_lists_commands_in_reverse_order() {
+    let vars = temp_path("preview_vars");
+    std::fs::write(&vars, r#"{"release": "v2"}"#).unwrap();
+
+    let mut chain = CommandChain::new("chain");
+    chain
+        .add_command(ShellCommand::new("create", "mkdir build").with_rollback("rmdir build"))
+        .add_command(
+            ShellCommand::new("deploy", "echo deploy")
+                .with_variables_file(&vars.display().to_string())
+                .with_rollback("echo undo {release}"),
+        )
+        .add_command(ShellCommand::new("notify", "echo done"));
+
+    let preview = chain.preview_rollback();
+    let _ = std::fs::remove_file(&vars);
+
+    assert_eq!(preview.len(), 3);
+    assert!(preview[0].starts_with("notify:"));
+    assert_eq!(preview[1], "deploy: echo undo v2");
+    assert_eq!(preview[2], "create: rmdir build");
+}
+
+#[tokio::test]
+async fn parallel_results_keep_insertion_order() {
+    let mut chain = CommandChain::new("chain");
+    chain
+        .with_execution_mode(ChainExecutionMode::Parallel)
+        .add_command(ShellCommand::new("slow", "sleep 0.3"))
+        .add_command(ShellCommand::new("fast", "true"));
+
+    let result = chain.execute().await.unwrap();
+
+    assert!(result.success);
+    let names: Vec<_> = result
+        .results
+        .iter()
+        .map(|r| r.command_name.as_str())
+        .collect();
+    assert_eq!(names, vec!["slow", "fast"]);
+    assert!(result.results[1].end_time < result.results[0].end_time);
+}
+
+#[tokio::test]
+async fn rollback_receives_forward_command_output() {
+    let log = temp_path("rollback_output");
+
+    let mut chain = CommandChain::new("chain");
+    chain
+        .add_command(
+            ShellCommand::new("create", "echo resource-42")
+                .with_rollback(&format!("echo delete {{__output__}} >> {}", log.display())),
+        )
+        .add_command(ShellCommand::new("fail", "false"));
+
+    let result = chain.execute().await.unwrap();
+
+    assert!(!result.success);
+    assert!(result.rolled_back);
+    assert_eq!(read_lines(&log), vec!["delete resource-42".to_string()]);
+    let _ = std::fs::remove_file(&log);
+}
+
+#[tokio::test]
+async fn cancellation_interrupts_running_command_promptly() {
+    let mut chain = CommandChain::new("chain");
+    chain
+        .add_command(ShellCommand::new("first", "true"))
+        .add_command(ShellCommand::new("wait", "sleep 5"))
+        .add_command(ShellCommand::new("last", "true"));
+
+    let token = CancellationToken::new();
+    let canceller = token.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        canceller.cancel();
+    });
+
+    let started = Instant::now();
+    let result = chain.execute_cancellable(token).await;
+
+    assert!(matches!(result, Err(CommandError::Interrupted(_))));
+    assert!(started.elapsed() < Duration::from_secs(2));
+}
+
+#[tokio::test]
+async fn chain_env_reaches_commands_unless_overridden() {
+    let mut chain = CommandChain::new("chain");
+    chain
+        .with_env_var("STAGE", "prod")
+        .add_command(ShellCommand::new("first", "sh -c 'echo $STAGE'"))
+        .add_command(ShellCommand::new("second", "sh -c 'echo $STAGE'"))
+        .add_command(
+            ShellCommand::new("override", "sh -c 'echo $STAGE'").with_env_var("STAGE", "test"),
+        );
+
+    let result = chain.execute().await.unwrap();
+
+    assert!(result.success);
+    let outputs: Vec<_> = result.results.iter().map(|r| r.output.trim()).collect();
+    assert_eq!(outputs, vec!["prod", "prod", "test"]);
+}
+
+#[tokio::test]
+async fn command_log_context_includes_command_name() {
+    let logger = MemoryLogger::new();
+
+    let mut chain = CommandChain::new("deploy");
+    chain
+        .with_logger(logger.boxed())
+        .add_command(ShellCommand::new("build", "true"));
+
+    assert!(chain.execute().await.unwrap().success);
+
+    let command_entries: Vec<_> = logger
+        .entries()
+        .into_iter()
+        .filter_map(|entry| entry.context.and_then(|context| context.extra))
+        .filter(|extra| extra.get("command").is_some())
+        .collect();
+
+    assert!(!command_entries.is_empty());
+    for extra in command_entries {
+        assert_eq!(extra["command"], "build");
+        assert_eq!(extra["chain"], "deploy");
+    }
+}
+
+#[tokio::test]
+async fn null_logger_does_not_change_result() {
+    let build = |logger: Option<NullLogger>| {
+        let mut chain = CommandChain::new("chain");
+        if let Some(logger) = logger {
+            chain.with_logger(Box::new(logger));
+        }
+        chain
+            .with_rollback_on_error(false)
+            .add_command(ShellCommand::new("ok", "echo done"))
+            .add_command(ShellCommand::new("fail", "sh -c 'echo oops 1>&2; exit 3'"));
+        chain
+    };
+
+    let plain = build(None).execute().await.unwrap();
+    let silent = build(Some(NullLogger::new())).execute().await.unwrap();
+
+    assert_eq!(silent.success, plain.success);
+    assert_eq!(silent.error, plain.error);
+    assert_eq!(silent.errors, plain.errors);
+    assert_eq!(silent.rolled_back, plain.rolled_back);
+    let summary = |result: &ChainResult| {
+        result
+            .results
+            .iter()
+            .map(|r| {
+                (
+                    r.command_name.clone(),
+                    r.status,
+                    r.exit_code,
+                    r.output.clone(),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(summary(&silent), summary(&plain));
+}
+
+#[tokio::test]
+async fn shared_command_runs_in_both_chains() {
+    let log = temp_path("shared_command");
+    let shared: Arc<dyn Command> = Arc::new(ShellCommand::new(
+        "record",
+        &format!("echo run >> {}", log.display()),
+    ));
+
+    let mut first = CommandChain::new("first");
+    first.add_shared_command(Arc::clone(&shared));
+    let mut second = CommandChain::new("second");
+    second.add_shared_command(Arc::clone(&shared));
+
+    assert!(first.execute().await.unwrap().success);
+    assert!(second.execute().await.unwrap().success);
+
+    assert_eq!(read_lines(&log), vec!["run".to_string(), "run".to_string()]);
+    let _ = std::fs::remove_file(&log);
+}
+
+#[tokio::test]
+async fn auto_mode_reports_chosen_mode() {
+    let mut mixed = CommandChain::new("mixed");
+    mixed
+        .with_execution_mode(ChainExecutionMode::Auto)
+        .add_command(
+            ShellCommand::new("first", "true").with_execution_mode(ExecutionMode::Parallel),
+        )
+        .add_command(
+            ShellCommand::new("second", "true").with_execution_mode(ExecutionMode::Sequential),
+        );
+
+    let mut parallel = CommandChain::new("parallel");
+    parallel
+        .with_execution_mode(ChainExecutionMode::Auto)
+        .add_command(
+            ShellCommand::new("first", "true").with_execution_mode(ExecutionMode::Parallel),
+        )
+        .add_command(
+            ShellCommand::new("second", "true").with_execution_mode(ExecutionMode::Parallel),
+        );
+
+    assert_eq!(
+        mixed.execute().await.unwrap().chosen_mode,
+        ExecutionMode::Sequential
+    );
+    assert_eq!(
+        parallel.execute().await.unwrap().chosen_mode,
+        ExecutionMode::Parallel
+    );
+}
+
+#[tokio::test]
+async fn summary_identifies_slowest_command() {
+    let mut chain = CommandChain::new("chain");
+    chain
+        .add_command(ShellCommand::new("short", "sleep 0.05"))
+        .add_command(ShellCommand::new("long", "sleep 0.4"))
+        .add_command(ShellCommand::new("medium", "sleep 0.15"));
+
+    let result = chain.execute().await.unwrap();
+    let summary = result.summary();
+
+    assert_eq!(summary.slowest_command.as_deref(), Some("long"));
+    assert_eq!(summary.succeeded, 3);
+    assert_eq!(summary.failed, 0);
+    assert!(summary.total_duration_ms >= 600);
+}
+
+#[test]
+fn execute_blocking_runs_chain_without_async_runtime() {
+    let mut chain = CommandChain::new("chain");
+    chain
+        .add_command(ShellCommand::new("first", "echo one"))
+        .add_command(ShellCommand::new("second", "echo two"));
+
+    let result = chain.execute_blocking().unwrap();
+
+    assert!(result.success);
+    assert_eq!(result.results.len(), 2);
+}
+
+#[tokio::test]
+async fn progress_callback_reports_each_completed_command() {
+    let calls = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let recorded = Arc::clone(&calls);
+
+    let mut chain = CommandChain::new("chain");
+    chain
+        .with_on_progress(Box::new(move |completed, total| {
+            recorded.lock().unwrap().push((completed, total));
+        }))
+        .add_command(ShellCommand::new("first", "true"))
+        .add_command(ShellCommand::new("second", "true"))
+        .add_command(ShellCommand::new("third", "true"));
+
+    assert!(chain.execute().await.unwrap().success);
+
+    assert_eq!(*calls.lock().unwrap(), vec![(1, 3), (2, 3), (3, 3)]);
+}
+
+#[tokio::test]
+async fn rollback_succeeds_after_retry() {
+    let marker = temp_path("flaky_rollback");
+
+    let mut chain = CommandChain::new("chain");
+    chain
+        .add_command(
+            ShellCommand::new("first", "true")
+                .with_rollback(&format!(
+                    "sh -c 'test -e {0} || (touch {0}; exit 1)'",
+                    marker.display()
+                ))
+                .with_rollback_retries(2),
+        )
+        .add_command(ShellCommand::new("second", "false"));
+
+    let result = chain.execute().await.unwrap();
+
+    assert!(!result.success);
+    assert!(result.rolled_back);
+    assert_eq!(result.rollback_results.len(), 1);
+    assert!(result.rollback_results[0].success);
+    assert!(marker.exists());
+    let _ = std::fs::remove_file(&marker);
+}
+
+#[tokio::test]
+async fn default_timeout_applies_to_commands_without_own_timeout() {
+    let mut chain = CommandChain::new("chain");
+    chain
+        .with_execution_mode(ChainExecutionMode::Parallel)
+        .with_rollback_on_error(false)
+        .with_default_timeout(Duration::from_millis(300))
+        .add_command(ShellCommand::new("inherits", "sleep 2"))
+        .add_command(
+            ShellCommand::new("own", "sleep 0.5").with_timeout_duration(Duration::from_secs(5)),
+        );
+
+    let started = Instant::now();
+    let result = chain.execute().await.unwrap();
+
+    assert!(!result.success);
+    assert!(result.results[0].is_timeout());
+    assert!(result.results[1].success);
+    assert!(started.elapsed() < Duration::from_secs(2));
+}
+
+#[tokio::test]
+async fn display_lists_commands_and_status() {
+    let mut chain = CommandChain::new("chain");
+    chain
+        .with_rollback_on_error(false)
+        .with_execution_mode(ChainExecutionMode::Parallel)
+        .add_command(ShellCommand::new("build", "echo built"))
+        .add_command(ShellCommand::new("publish", "false"));
+
+    let report = chain.execute().await.unwrap().to_string();
+
+    assert!(report.starts_with("Цепочка: ОШИБКА"));
+    assert!(report.contains("[OK] build: "));
+    assert!(report.contains("[ОШИБКА] publish: "));
+
+    let mut passing = CommandChain::new("passing");
+    passing.add_command(ShellCommand::new("build", "true"));
+    let report = passing.execute().await.unwrap().to_string();
+
+    assert!(report.starts_with("Цепочка: успешно"));
+    assert!(report.contains("build"));
+}
+
+#[tokio::test]
+async fn on_error_runs_without_rolling_back_other_commands() {
+    let log = temp_path("on_error");
+    let log_path = log.display().to_string();
+
+    let mut chain = CommandChain::new("chain");
+    chain
+        .with_rollback_on_error(false)
+        .add_command(
+            ShellCommand::new("prepare", "true")
+                .with_rollback(&format!("echo rollback-prepare >> {}", log_path)),
+        )
+        .add_command(
+            ShellCommand::new("migrate", "false")
+                .with_on_error(&format!("echo cleanup-migrate >> {}", log_path)),
+        );
+
+    let result = chain.execute().await.unwrap();
+
+    assert!(!result.success);
+    assert!(!result.rolled_back);
+    assert_eq!(read_lines(&log), vec!["cleanup-migrate".to_string()]);
+    let _ = std::fs::remove_file(&log);
+}
+
+#[tokio::test]
+async fn execute_with_mode_overrides_chain_mode() {
+    let mut chain = CommandChain::new("chain");
+    chain
+        .with_execution_mode(ChainExecutionMode::Sequential)
+        .add_command(ShellCommand::new("first", "sleep 0.3"))
+        .add_command(ShellCommand::new("second", "sleep 0.3"))
+        .add_command(ShellCommand::new("third", "sleep 0.3"));
+
+    let started = Instant::now();
+    let result = chain
+        .execute_with_mode(ChainExecutionMode::Parallel)
+        .await
+        .unwrap();
+
+    assert!(result.success);
+    assert_eq!(result.chosen_mode, ExecutionMode::Parallel);
+    assert!(started.elapsed() < Duration::from_millis(800));
+}
+
+#[tokio::test]
+async fn add_shell_calls_can_be_chained() {
+    let mut chain = CommandChain::new("chain");
+    chain
+        .add_shell("first", "echo one")
+        .add_shell("second", "echo two");
+
+    let result = chain.execute().await.unwrap();
+
+    assert!(result.success);
+    let outputs: Vec<_> = result.results.iter().map(|r| r.output.trim()).collect();
+    assert_eq!(outputs, vec!["one", "two"]);
+}
+
+#[tokio::test]
+async fn parallel_failure_keeps_all_results() {
+    let mut chain = CommandChain::new("chain");
+    chain
+        .with_execution_mode(ChainExecutionMode::Parallel)
+        .with_rollback_on_error(false)
+        .add_command(ShellCommand::new("first", "echo one"))
+        .add_command(ShellCommand::new("broken", "exit 3"))
+        .add_command(ShellCommand::new("third", "echo three"));
+
+    let result = chain.execute().await.unwrap();
+
+    assert!(!result.success);
+    assert_eq!(result.results.len(), 3);
+    assert_eq!(result.results[0].output, "one\n");
+    assert_eq!(result.results[1].exit_code, Some(3));
+    assert_eq!(result.results[2].output, "three\n");
+}
+
+#[tokio::test]
+async fn summary_line_is_logged_at_completion() {
+    let logger = MemoryLogger::new();
+
+    let mut chain = CommandChain::new("deploy");
+    chain
+        .with_logger(logger.boxed())
+        .with_rollback_on_error(false)
+        .add_command(ShellCommand::new("build", "true"))
+        .add_command(ShellCommand::new("publish", "false"));
+
+    assert!(!chain.execute().await.unwrap().success);
+
+    let entry = logger
+        .entries()
+        .into_iter()
+        .find(|entry| entry.message.starts_with("Итог цепочки 'deploy'"))
+        .expect("summary line is logged");
+    assert_eq!(entry.level, Some(LogLevel::Error));
+
+    let extra = entry.context.and_then(|context| context.extra).unwrap();
+    assert_eq!(extra["chain"], "deploy");
+    assert_eq!(extra["success"], false);
+    assert_eq!(extra["commands"], 2);
+    assert_eq!(extra["succeeded"], 1);
+    assert_eq!(extra["failed"], 1);
+    assert_eq!(extra["skipped"], 0);
+    assert_eq!(extra["rolled_back"], false);
+    assert!(extra["duration_ms"].is_u64());
+}
+
+#[tokio::test]
+async fn heartbeat_carries_chain_name() {
+    let logger = MemoryLogger::new();
+
+    let mut chain = CommandChain::new("deploy");
+    chain.with_logger(logger.boxed()).add_command(
+        ShellCommand::new("slow", "sleep 0.35").with_heartbeat(Duration::from_millis(100)),
+    );
+
+    assert!(chain.execute().await.unwrap().success);
+
+    let heartbeats: Vec<_> = logger
+        .entries()
+        .into_iter()
+        .filter(|entry| entry.message.contains("выполняется (прошло"))
+        .collect();
+
+    assert!(!heartbeats.is_empty());
+    for entry in heartbeats {
+        let extra = entry.context.and_then(|context| context.extra).unwrap();
+        assert_eq!(extra["chain"], "deploy");
+        assert_eq!(extra["command"], "slow");
+    }
+}
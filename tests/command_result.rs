@@ -0,0 +1,31 @@
+use command_system::{CommandResult, CommandStatus};
+
+#[test]
+fn status_reflects_result_kind() {
+    let success = CommandResult::new("ok").success("done".to_string());
+    assert_eq!(success.status, CommandStatus::Success);
+    assert!(success.success);
+
+    let failed = CommandResult::new("fail").failure("boom".to_string(), Some(1));
+    assert_eq!(failed.status, CommandStatus::Failed);
+    assert!(!failed.success && !failed.is_timeout());
+
+    let timed_out = CommandResult::new("slow").timeout("too slow".to_string());
+    assert_eq!(timed_out.status, CommandStatus::TimedOut);
+    assert!(timed_out.is_timeout() && !timed_out.success);
+
+    let skipped = CommandResult::new("skip").skip("not needed");
+    assert_eq!(skipped.status, CommandStatus::Skipped);
+    assert!(skipped.is_skipped() && skipped.success);
+}
+
+#[test]
+fn missing_status_deserializes_as_failed() {
+    let mut value = serde_json::to_value(CommandResult::new("old")).unwrap();
+    value.as_object_mut().unwrap().remove("status");
+
+    let result: CommandResult = serde_json::from_value(value).unwrap();
+
+    assert_eq!(result.status, CommandStatus::Failed);
+    assert!(!result.is_timeout() && !result.is_skipped());
+}
@@ -0,0 +1,124 @@
+//! Общие вспомогательные средства интеграционных тестов
+
+#![allow(dead_code)]
+
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use command_system::logging::{LogContext, LogLevel, Logger, OutputStream};
+
+/// Запись, сохраненная логгером `MemoryLogger`
+#[derive(Debug, Clone)]
+pub struct Entry {
+    /// Уровень сообщения (`None` для строк вывода команд)
+    pub level: Option<LogLevel>,
+
+    /// Поток вывода (только для строк вывода команд)
+    pub stream: Option<OutputStream>,
+
+    /// Текст сообщения
+    pub message: String,
+
+    /// Контекст сообщения
+    pub context: Option<LogContext>,
+}
+
+/// Логгер, сохраняющий сообщения в памяти для проверки в тестах
+#[derive(Clone, Default)]
+pub struct MemoryLogger {
+    entries: Arc<Mutex<Vec<Entry>>>,
+}
+
+impl MemoryLogger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Возвращает копию логгера, пригодную для передачи в цепочку
+    pub fn boxed(&self) -> Box<dyn Logger> {
+        Box::new(self.clone())
+    }
+
+    /// Возвращает сохраненные записи
+    pub fn entries(&self) -> Vec<Entry> {
+        self.entries.lock().unwrap().clone()
+    }
+
+    /// Возвращает тексты сохраненных сообщений
+    pub fn messages(&self) -> Vec<String> {
+        self.entries().into_iter().map(|e| e.message).collect()
+    }
+
+    /// Проверяет, есть ли сообщение, содержащее `text`
+    pub fn contains(&self, text: &str) -> bool {
+        self.messages().iter().any(|m| m.contains(text))
+    }
+}
+
+impl Logger for MemoryLogger {
+    fn log(&self, level: LogLevel, message: &str) {
+        self.entries.lock().unwrap().push(Entry {
+            level: Some(level),
+            stream: None,
+            message: message.to_string(),
+            context: None,
+        });
+    }
+
+    fn log_with_context(&self, level: LogLevel, message: &str, context: &LogContext) {
+        self.entries.lock().unwrap().push(Entry {
+            level: Some(level),
+            stream: None,
+            message: message.to_string(),
+            context: Some(context.clone()),
+        });
+    }
+
+    fn log_output(&self, stream: OutputStream, line: &str) {
+        self.entries.lock().unwrap().push(Entry {
+            level: None,
+            stream: Some(stream),
+            message: line.to_string(),
+            context: None,
+        });
+    }
+}
+
+/// Возвращает уникальный путь во временном каталоге
+pub fn temp_path(prefix: &str) -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "command_system_{}_{}",
+        prefix,
+        uuid::Uuid::new_v4()
+    ))
+}
+
+/// Возвращает строки файла (пустой список, если файла нет)
+pub fn read_lines(path: &std::path::Path) -> Vec<String> {
+    std::fs::read_to_string(path)
+        .map(|s| s.lines().map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+/// Приемник вывода консольного логгера, доступный из теста
+#[derive(Clone, Default)]
+pub struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuffer {
+    /// Возвращает записанный текст
+    pub fn contents(&self) -> String {
+        String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+    }
+}
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
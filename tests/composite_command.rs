@@ -0,0 +1,180 @@
+mod common;
+
+use std::time::{Duration, Instant};
+
+use command_system::command::{
+    CommandExecution, CompositeCommand, ExecutionContext, ExecutionMode, ShellCommand,
+};
+use command_system::{CommandChain, CompositeCommandBuilder};
+
+use common::{read_lines, temp_path};
+
+#[tokio::test]
+async fn internal_rollback_is_not_repeated_by_chain() {
+    let log = temp_path("composite_rollback");
+    let log_path = log.display().to_string();
+
+    let mut composite = CompositeCommand::new("group");
+    composite
+        .with_rollback_on_error(true)
+        .add_command(
+            ShellCommand::new("first", "true")
+                .with_rollback(&format!("echo first >> {}", log_path)),
+        )
+        .add_command(ShellCommand::new("second", "false"));
+
+    let mut chain = CommandChain::new("chain");
+    chain.with_rollback_on_error(true).add_command(composite);
+
+    let result = chain.execute().await.unwrap();
+
+    assert!(!result.success);
+    assert_eq!(read_lines(&log), vec!["first".to_string()]);
+    let _ = std::fs::remove_file(&log);
+}
+
+#[tokio::test]
+async fn builder_creates_parallel_composite() {
+    let composite = CompositeCommandBuilder::new("group")
+        .execution_mode(ExecutionMode::Parallel)
+        .command(ShellCommand::new("first", "sh -c 'sleep 0.3; echo first'"))
+        .command(ShellCommand::new(
+            "second",
+            "sh -c 'sleep 0.3; echo second'",
+        ))
+        .build();
+
+    assert_eq!(composite.execution_mode(), ExecutionMode::Parallel);
+
+    let started = Instant::now();
+    let result = composite.execute().await.unwrap();
+
+    assert!(result.success);
+    assert!(result.output.contains("first"));
+    assert!(result.output.contains("second"));
+    assert!(started.elapsed() < Duration::from_millis(550));
+}
+
+#[tokio::test]
+async fn failed_subcommand_rolls_back_earlier_ones() {
+    let log = temp_path("composite_rollback_on_error");
+    let log_path = log.display().to_string();
+
+    let mut composite = CompositeCommand::new("group");
+    composite
+        .with_rollback_on_error(true)
+        .add_command(
+            ShellCommand::new("first", &format!("echo first >> {}", log_path))
+                .with_rollback(&format!("echo undo-first >> {}", log_path)),
+        )
+        .add_command(ShellCommand::new("second", "false"))
+        .add_command(ShellCommand::new(
+            "third",
+            &format!("echo third >> {}", log_path),
+        ));
+
+    let result = composite.execute().await.unwrap();
+
+    assert!(!result.success);
+    assert_eq!(
+        read_lines(&log),
+        vec!["first".to_string(), "undo-first".to_string()]
+    );
+    let _ = std::fs::remove_file(&log);
+}
+
+#[tokio::test]
+async fn concurrency_limit_is_respected() {
+    let log = temp_path("composite_concurrency");
+    let log_path = log.display().to_string();
+
+    let mut composite = CompositeCommand::new("group");
+    composite
+        .with_execution_mode(ExecutionMode::Parallel)
+        .with_max_concurrency(2);
+    for i in 0..5 {
+        composite.add_command(ShellCommand::new(
+            &format!("step{}", i),
+            &format!(
+                "sh -c 'echo start >> {0}; sleep 0.2; echo end >> {0}'",
+                log_path
+            ),
+        ));
+    }
+
+    let result = composite.execute().await.unwrap();
+    assert!(result.success);
+
+    let mut running = 0;
+    let mut max_running = 0;
+    for line in read_lines(&log) {
+        if line == "start" {
+            running += 1;
+            max_running = max_running.max(running);
+        } else {
+            running -= 1;
+        }
+    }
+
+    assert_eq!(max_running, 2);
+    let _ = std::fs::remove_file(&log);
+}
+
+#[tokio::test]
+async fn higher_priority_runs_first_with_limit_of_one() {
+    let log = temp_path("composite_priority");
+
+    let mut composite = CompositeCommand::new("group");
+    composite
+        .with_execution_mode(ExecutionMode::Parallel)
+        .with_max_concurrency(1);
+    for (name, priority) in [("low", 1), ("high", 10), ("medium", 5)] {
+        composite.add_command(
+            ShellCommand::new(name, &format!("echo {} >> {}", name, log.display()))
+                .with_priority(priority),
+        );
+    }
+
+    let result = composite.execute().await.unwrap();
+    assert!(result.success);
+
+    assert_eq!(read_lines(&log), vec!["high", "medium", "low"]);
+    let _ = std::fs::remove_file(&log);
+}
+
+#[tokio::test]
+async fn internal_rollback_is_tracked_per_run() {
+    let log = temp_path("composite_run_rollback");
+    let marker = temp_path("composite_run_marker");
+    std::fs::write(&marker, "").unwrap();
+
+    let mut composite = CompositeCommand::new("group");
+    composite
+        .with_rollback_on_error(true)
+        .add_command(
+            ShellCommand::new("first", "true")
+                .with_rollback(&format!("echo undo >> {}", log.display())),
+        )
+        .add_command(ShellCommand::new(
+            "check",
+            &format!("test -f {}", marker.display()),
+        ));
+
+    // Первый запуск успешен, второй завершается ошибкой и откатывается сам
+    let succeeded = composite.execute().await.unwrap();
+    std::fs::remove_file(&marker).unwrap();
+    let failed = composite.execute().await.unwrap();
+    assert!(succeeded.success);
+    assert!(!failed.success);
+    assert_eq!(read_lines(&log), vec!["undo"]);
+
+    let context = ExecutionContext::new().with_forward_result(&succeeded);
+    composite.rollback_with_context(&context).await.unwrap();
+    assert_eq!(read_lines(&log), vec!["undo", "undo"]);
+
+    let context = ExecutionContext::new().with_forward_result(&failed);
+    composite.rollback_with_context(&context).await.unwrap();
+    assert_eq!(read_lines(&log), vec!["undo", "undo"]);
+
+    let _ = std::fs::remove_file(&log);
+}
@@ -0,0 +1,119 @@
+mod common;
+
+use command_system::command::ShellCommand;
+use command_system::{ChainExecutionMode, CommandChain, CommandResult, ConditionalCommand};
+
+use common::temp_path;
+
+/// Условие: предыдущая команда с указанным именем вывела непустую строку
+fn printed(name: &'static str) -> impl Fn(&[CommandResult]) -> bool + Send + Sync {
+    move |results| {
+        results
+            .iter()
+            .any(|r| r.command_name == name && !r.output.trim().is_empty())
+    }
+}
+
+#[tokio::test]
+async fn runs_when_predicate_holds() {
+    let mut chain = CommandChain::new("chain");
+    chain
+        .add_command(ShellCommand::new("build", "echo artifact"))
+        .add_command(ConditionalCommand::new(
+            ShellCommand::new("deploy", "echo deployed"),
+            printed("build"),
+        ));
+
+    let result = chain.execute().await.unwrap();
+
+    assert!(result.success);
+    assert!(!result.results[1].is_skipped());
+    assert_eq!(result.results[1].output.trim(), "deployed");
+}
+
+#[tokio::test]
+async fn skips_when_predicate_fails() {
+    let mut chain = CommandChain::new("chain");
+    chain
+        .add_command(ShellCommand::new("build", "true"))
+        .add_command(ConditionalCommand::new(
+            ShellCommand::new("deploy", "echo deployed"),
+            printed("build"),
+        ));
+
+    let result = chain.execute().await.unwrap();
+
+    assert!(result.success);
+    assert!(result.results[1].is_skipped());
+}
+
+#[tokio::test]
+async fn dag_passes_dependency_results() {
+    let mut chain = CommandChain::new("chain");
+    chain
+        .with_execution_mode(ChainExecutionMode::Dag)
+        .add_command(ShellCommand::new("build", "echo artifact"))
+        .add_command(ConditionalCommand::new(
+            ShellCommand::new("deploy", "echo deployed").with_depends_on(&["build"]),
+            printed("build"),
+        ));
+
+    let result = chain.execute().await.unwrap();
+
+    let deploy = result
+        .results
+        .iter()
+        .find(|r| r.command_name == "deploy")
+        .unwrap();
+    assert!(!deploy.is_skipped());
+    assert_eq!(deploy.output.trim(), "deployed");
+}
+
+#[tokio::test]
+async fn path_conditions_follow_existing_file() {
+    let marker = temp_path("conditional_marker");
+
+    let mut chain = CommandChain::new("chain");
+    chain
+        .add_command(ShellCommand::new(
+            "create",
+            &format!("touch {}", marker.display()),
+        ))
+        .add_command(ConditionalCommand::if_path_exists(
+            ShellCommand::new("deploy", "echo deployed"),
+            &marker,
+        ))
+        .add_command(ConditionalCommand::if_path_missing(
+            ShellCommand::new("init", "echo initialized"),
+            &marker,
+        ));
+
+    let result = chain.execute().await.unwrap();
+
+    assert!(result.success);
+    assert_eq!(result.results[1].output.trim(), "deployed");
+    assert!(result.results[2].is_skipped());
+    let _ = std::fs::remove_file(&marker);
+}
+
+#[tokio::test]
+async fn path_conditions_follow_missing_file() {
+    let marker = temp_path("conditional_missing");
+
+    let mut chain = CommandChain::new("chain");
+    chain
+        .add_command(ConditionalCommand::if_path_exists(
+            ShellCommand::new("deploy", "echo deployed"),
+            &marker,
+        ))
+        .add_command(ConditionalCommand::if_path_missing(
+            ShellCommand::new("init", "echo initialized"),
+            &marker,
+        ));
+
+    let result = chain.execute().await.unwrap();
+
+    assert!(result.success);
+    assert!(result.results[0].is_skipped());
+    assert_eq!(result.results[1].output.trim(), "initialized");
+}
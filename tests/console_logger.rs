@@ -0,0 +1,130 @@
+mod common;
+
+use command_system::logging::{ConsoleTarget, LogContext, OutputStream};
+use command_system::{ConsoleLogger, LogFormatter, LogLevel, Logger};
+
+use common::SharedBuffer;
+
+/// Создает логгер, пишущий в буфер
+fn buffered(buffer: &SharedBuffer) -> ConsoleLogger {
+    ConsoleLogger::new(LogLevel::Info).with_writer(Box::new(buffer.clone()))
+}
+
+#[test]
+fn stderr_output_is_colored_when_colors_are_enabled() {
+    let buffer = SharedBuffer::default();
+    let logger = buffered(&buffer).with_colors(true);
+
+    logger.log_output(OutputStream::Stdout, "plain line");
+    logger.log_output(OutputStream::Stderr, "error line");
+
+    let contents = buffer.contents();
+    let lines: Vec<_> = contents.lines().collect();
+    assert_eq!(lines[0], "plain line");
+    assert!(lines[1].contains("\x1b["));
+    assert!(lines[1].contains("error line"));
+}
+
+#[test]
+fn output_is_not_colored_without_terminal() {
+    let buffer = SharedBuffer::default();
+    let logger = buffered(&buffer);
+
+    logger.log_output(OutputStream::Stderr, "error line");
+
+    assert_eq!(buffer.contents(), "error line\n");
+}
+
+#[test]
+fn output_coloring_can_be_disabled() {
+    let buffer = SharedBuffer::default();
+    let logger = buffered(&buffer)
+        .with_colors(true)
+        .with_output_coloring(false);
+
+    logger.log_output(OutputStream::Stderr, "error line");
+
+    assert_eq!(buffer.contents(), "error line\n");
+}
+
+/// Форматировщик, добавляющий префикс приложения
+struct PrefixFormatter;
+
+impl LogFormatter for PrefixFormatter {
+    fn format(&self, level: LogLevel, message: &str, _context: Option<&LogContext>) -> String {
+        format!("MYAPP {} {}", level.as_str(), message)
+    }
+}
+
+#[test]
+fn custom_formatter_shapes_log_lines() {
+    let buffer = SharedBuffer::default();
+    let logger = buffered(&buffer).with_formatter(Box::new(PrefixFormatter));
+
+    logger.info("started");
+
+    assert_eq!(buffer.contents(), "MYAPP INFO started\n");
+}
+
+#[test]
+fn error_messages_route_to_stderr_target() {
+    let stdout = SharedBuffer::default();
+    let stderr = SharedBuffer::default();
+    let logger = buffered(&stdout).with_stderr_writer(Box::new(stderr.clone()));
+
+    logger.info("progress");
+    logger.error("failure");
+
+    assert!(stdout.contents().contains("progress"));
+    assert!(!stdout.contents().contains("failure"));
+    assert!(stderr.contents().contains("failure"));
+    assert!(!stderr.contents().contains("progress"));
+}
+
+#[test]
+fn stderr_stream_routes_all_messages_to_stderr_target() {
+    let stdout = SharedBuffer::default();
+    let stderr = SharedBuffer::default();
+    let logger = buffered(&stdout)
+        .with_stderr_writer(Box::new(stderr.clone()))
+        .with_stream(ConsoleTarget::Stderr);
+
+    logger.info("progress");
+    logger.error("failure");
+
+    assert_eq!(stdout.contents(), "");
+    assert!(stderr.contents().contains("progress"));
+    assert!(stderr.contents().contains("failure"));
+}
+
+#[test]
+fn formatted_line_is_written_to_in_memory_sink() {
+    let buffer = SharedBuffer::default();
+    let logger = buffered(&buffer).with_time_format("TIME");
+
+    logger.warning("disk almost full");
+
+    assert_eq!(buffer.contents(), "TIME [WARNING] disk almost full\n");
+}
+
+#[test]
+fn json_mode_emits_parseable_lines() {
+    let buffer = SharedBuffer::default();
+    let logger = ConsoleLogger::json(LogLevel::Info).with_writer(Box::new(buffer.clone()));
+
+    logger.info("chain started");
+    logger.log_output(OutputStream::Stderr, "warning from command");
+
+    let contents = buffer.contents();
+    let lines: Vec<serde_json::Value> = contents
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0]["level"], "INFO");
+    assert_eq!(lines[0]["message"], "chain started");
+    assert!(lines[0]["timestamp"].is_string());
+    assert_eq!(lines[1]["level"], "WARNING");
+    assert_eq!(lines[1]["message"], "warning from command");
+}
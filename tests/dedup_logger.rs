@@ -0,0 +1,37 @@
+mod common;
+
+use command_system::{DedupLogger, Logger};
+
+use common::MemoryLogger;
+
+#[test]
+fn repeated_message_is_reported_once_with_count() {
+    let memory = MemoryLogger::new();
+    let logger = DedupLogger::new(memory.boxed());
+
+    for _ in 0..5 {
+        logger.warning("Повтор подключения");
+    }
+    logger.flush();
+
+    assert_eq!(
+        memory.messages(),
+        vec!["Повтор подключения", "(повторов предыдущего сообщения: 4)"]
+    );
+}
+
+#[test]
+fn different_message_reports_pending_repeats() {
+    let memory = MemoryLogger::new();
+    let logger = DedupLogger::new(memory.boxed());
+
+    logger.info("build");
+    logger.info("build");
+    logger.info("test");
+    drop(logger);
+
+    assert_eq!(
+        memory.messages(),
+        vec!["build", "(повторов предыдущего сообщения: 1)", "test"]
+    );
+}
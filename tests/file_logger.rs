@@ -0,0 +1,51 @@
+mod common;
+
+use std::time::{Duration, Instant};
+
+use command_system::logging::{AsyncFileLogger, FileLogger, LogLevel, Logger};
+
+use common::{read_lines, temp_path};
+
+#[test]
+fn buffered_entries_are_complete_json_lines_after_flush() {
+    let path = temp_path("file_logger");
+    let logger = FileLogger::new(LogLevel::Debug, &path.display().to_string());
+
+    for i in 0..10_000 {
+        logger.info(&format!("сообщение {}", i));
+    }
+    logger.flush().unwrap();
+
+    let lines = read_lines(&path);
+    assert_eq!(lines.len(), 10_000);
+    for (i, line) in lines.iter().enumerate() {
+        let entry: serde_json::Value = serde_json::from_str(line).unwrap();
+        assert_eq!(entry["message"], format!("сообщение {}", i));
+    }
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn async_logger_writes_everything_after_shutdown() {
+    let path = temp_path("async_file_logger");
+    let logger = AsyncFileLogger::new(LogLevel::Info, &path.display().to_string());
+
+    // В однопоточной среде задача записи не выполняется, пока тест не уступит управление,
+    // поэтому `log` только передает записи в канал и сразу возвращается
+    let started = Instant::now();
+    for i in 0..1_000 {
+        logger.info(&format!("сообщение {}", i));
+    }
+    assert!(started.elapsed() < Duration::from_secs(1));
+    assert!(read_lines(&path).is_empty());
+
+    logger.shutdown().await;
+
+    let lines = read_lines(&path);
+    assert_eq!(lines.len(), 1_000);
+    let last: serde_json::Value = serde_json::from_str(&lines[999]).unwrap();
+    assert_eq!(last["message"], "сообщение 999");
+
+    let _ = std::fs::remove_file(&path);
+}
@@ -0,0 +1,34 @@
+mod common;
+
+use command_system::{FilteringLogger, LogLevel, Logger};
+
+use common::MemoryLogger;
+
+#[test]
+fn only_matching_messages_pass_through() {
+    let memory = MemoryLogger::new();
+    let logger = FilteringLogger::with_substring(memory.boxed(), "deploy");
+
+    logger.info("Выполнение команды: deploy");
+    logger.info("Выполнение команды: build");
+    logger.error("Ошибка команды deploy");
+
+    assert_eq!(
+        memory.messages(),
+        vec!["Выполнение команды: deploy", "Ошибка команды deploy"]
+    );
+}
+
+#[test]
+fn custom_filter_sees_level_and_message() {
+    let memory = MemoryLogger::new();
+    let logger = FilteringLogger::new(memory.boxed(), |level, message| {
+        level == LogLevel::Error || message.starts_with("build")
+    });
+
+    logger.info("build started");
+    logger.info("test started");
+    logger.error("test failed");
+
+    assert_eq!(memory.messages(), vec!["build started", "test failed"]);
+}
@@ -0,0 +1,52 @@
+mod common;
+
+use std::sync::Arc;
+
+use command_system::command::ShellCommand;
+use command_system::{CommandChain, ConsoleLogger, LogLevel, LoggerBuilder};
+
+use common::{temp_path, SharedBuffer};
+
+#[test]
+fn console_and_file_sinks_receive_message() {
+    let console = SharedBuffer::default();
+    let file = temp_path("builder_log");
+
+    let logger = LoggerBuilder::new()
+        .logger(Box::new(
+            ConsoleLogger::new(LogLevel::Info).with_writer(Box::new(console.clone())),
+        ))
+        .file(LogLevel::Info, &file.display().to_string())
+        .build();
+
+    logger.info("hello from builder");
+    drop(logger);
+
+    assert!(console.contents().contains("hello from builder"));
+    assert!(std::fs::read_to_string(&file)
+        .unwrap()
+        .contains("hello from builder"));
+    let _ = std::fs::remove_file(&file);
+}
+
+#[tokio::test]
+async fn shared_logger_serves_several_chains() {
+    let console = SharedBuffer::default();
+    let logger = LoggerBuilder::new()
+        .logger(Box::new(
+            ConsoleLogger::new(LogLevel::Info).with_writer(Box::new(console.clone())),
+        ))
+        .build_shared();
+
+    for name in ["first", "second"] {
+        let mut chain = CommandChain::new(name);
+        chain
+            .with_logger(Box::new(Arc::clone(&logger)))
+            .add_command(ShellCommand::new("step", "true"));
+        assert!(chain.execute().await.unwrap().success);
+    }
+
+    let contents = console.contents();
+    assert!(contents.contains("Цепочка 'first' успешно выполнена"));
+    assert!(contents.contains("Цепочка 'second' успешно выполнена"));
+}
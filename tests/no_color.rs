@@ -0,0 +1,24 @@
+//! Проверка переменной `NO_COLOR` вынесена в отдельный тест, так как она
+//! меняет окружение всего процесса
+mod common;
+
+use command_system::logging::OutputStream;
+use command_system::{ConsoleLogger, LogLevel, Logger};
+
+use common::SharedBuffer;
+
+#[test]
+fn no_color_disables_escape_sequences() {
+    std::env::set_var("NO_COLOR", "1");
+
+    let buffer = SharedBuffer::default();
+    let logger = ConsoleLogger::new(LogLevel::Info).with_writer(Box::new(buffer.clone()));
+
+    logger.error("failure");
+    logger.log_output(OutputStream::Stderr, "error line");
+
+    let contents = buffer.contents();
+    assert!(contents.contains("failure"));
+    assert!(contents.contains("error line"));
+    assert!(!contents.contains('\x1b'));
+}
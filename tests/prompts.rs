@@ -0,0 +1,117 @@
+//! Интерактивный ввод читается из стандартного ввода процесса, поэтому
+//! проверки запускают тест-помощник в отдельном процессе с заданным вводом.
+//! Без переменной окружения `HELPER_ENV` тесты-помощники ничего не делают.
+
+use std::io::Write;
+use std::process::Stdio;
+
+use command_system::command::{CommandExecution, ShellCommand};
+use command_system::CommandChain;
+
+/// Переменная окружения, включающая тесты-помощники
+const HELPER_ENV: &str = "COMMAND_SYSTEM_PROMPT_HELPER";
+
+/// Текст приглашения ко вводу значения переменной
+const PROMPT: &str = "Введите значение для";
+
+/// Запускает тест-помощник в отдельном процессе и возвращает его стандартный вывод
+fn run_helper(name: &str, input: &str) -> String {
+    let mut child = std::process::Command::new(std::env::current_exe().unwrap())
+        .args([name, "--exact", "--nocapture", "--test-threads=1"])
+        .env(HELPER_ENV, "1")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+/// Проверяет, запущен ли тест как помощник
+fn is_helper() -> bool {
+    std::env::var_os(HELPER_ENV).is_some()
+}
+
+#[tokio::test]
+async fn helper_secret_prompt() {
+    if !is_helper() {
+        return;
+    }
+
+    let result = ShellCommand::new("login", "echo token={!token}")
+        .execute()
+        .await
+        .unwrap();
+
+    println!("OUTPUT={}", result.output.trim());
+    println!("RESOLVED={}", result.resolved_command);
+}
+
+#[test]
+fn secret_value_is_substituted_and_redacted() {
+    let stdout = run_helper("helper_secret_prompt", "s3cret\n");
+
+    assert!(stdout.contains("OUTPUT=token=s3cret"));
+    assert!(stdout.contains("RESOLVED=echo token=***"));
+    assert_eq!(stdout.matches(PROMPT).count(), 1);
+}
+
+#[tokio::test]
+async fn helper_repeated_placeholder() {
+    if !is_helper() {
+        return;
+    }
+
+    let result = ShellCommand::new("greet", "echo {name} and {name}")
+        .execute()
+        .await
+        .unwrap();
+
+    println!("OUTPUT={}", result.output.trim());
+}
+
+#[test]
+fn repeated_placeholder_is_prompted_once() {
+    let stdout = run_helper("helper_repeated_placeholder", "alice\nbob\n");
+
+    assert!(stdout.contains("OUTPUT=alice and alice"));
+    assert_eq!(stdout.matches(PROMPT).count(), 1);
+}
+
+#[tokio::test]
+async fn helper_shared_chain_variable() {
+    if !is_helper() {
+        return;
+    }
+
+    let mut chain = CommandChain::new("chain");
+    chain
+        .with_share_variables(true)
+        .add_command(ShellCommand::new("first", "echo first {shared}"))
+        .add_command(ShellCommand::new("second", "echo second {shared}"));
+
+    let result = chain.execute().await.unwrap();
+
+    for command in &result.results {
+        println!("OUTPUT={}", command.output.trim());
+    }
+}
+
+#[test]
+fn shared_variable_is_prompted_once_per_chain() {
+    let stdout = run_helper("helper_shared_chain_variable", "v1\nv2\n");
+
+    assert!(stdout.contains("OUTPUT=first v1"));
+    assert!(stdout.contains("OUTPUT=second v1"));
+    assert_eq!(stdout.matches(PROMPT).count(), 1);
+}
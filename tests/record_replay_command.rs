@@ -0,0 +1,125 @@
+mod common;
+
+use std::collections::HashMap;
+
+use command_system::command::{
+    CommandExecution, ExecutionContext, RecordReplayCommand, RecordReplayMode, ShellCommand,
+};
+
+use common::{read_lines, temp_path};
+
+#[tokio::test]
+async fn cache_key_uses_substituted_command_and_chain_env() {
+    let command = RecordReplayCommand::new(ShellCommand::new("show", "echo {id}"), "unused.json");
+
+    let first = HashMap::from([("id".to_string(), "1".to_string())]);
+    let second = HashMap::from([("id".to_string(), "2".to_string())]);
+    let env = HashMap::from([("STAGE".to_string(), "prod".to_string())]);
+
+    let first_key = command
+        .cache_key(&ExecutionContext::new().with_variables(&first))
+        .await
+        .unwrap();
+    let second_key = command
+        .cache_key(&ExecutionContext::new().with_variables(&second))
+        .await
+        .unwrap();
+    let env_key = command
+        .cache_key(
+            &ExecutionContext::new()
+                .with_variables(&first)
+                .with_env(&env),
+        )
+        .await
+        .unwrap();
+
+    assert!(first_key.contains("echo 1"));
+    assert_ne!(first_key, second_key);
+    assert_ne!(first_key, env_key);
+    assert!(!env_key.contains("prod"));
+}
+
+#[tokio::test]
+async fn recordings_do_not_store_secrets_or_env_values() {
+    let file = temp_path("recordings_secret");
+    let recordings = file.display().to_string();
+    let env = HashMap::from([("API_KEY".to_string(), "env-value-42".to_string())]);
+
+    let command = RecordReplayCommand::new(
+        ShellCommand::new("login", "test -n {!token} && echo ok"),
+        &recordings,
+    )
+    .with_mode(RecordReplayMode::Record);
+
+    let overrides = HashMap::from([("token".to_string(), "s3cr3t".to_string())]);
+    let context = ExecutionContext::new()
+        .with_overrides(&overrides)
+        .with_env(&env);
+    let key = command.cache_key(&context).await.unwrap();
+    let result = command.execute_with_context(&context).await.unwrap();
+    assert!(result.success);
+
+    let other = HashMap::from([("token".to_string(), "other".to_string())]);
+    let other_key = command
+        .cache_key(
+            &ExecutionContext::new()
+                .with_overrides(&other)
+                .with_env(&env),
+        )
+        .await
+        .unwrap();
+
+    assert!(key.starts_with("login|test -n *** && echo ok|"));
+    assert_ne!(key, other_key);
+
+    let contents = std::fs::read_to_string(&file).unwrap();
+    assert!(contents.contains(&key));
+    assert!(!contents.contains("s3cr3t"));
+    assert!(!contents.contains("env-value-42"));
+    let _ = std::fs::remove_file(&file);
+}
+
+#[tokio::test]
+async fn failed_results_are_not_recorded_by_default() {
+    let file = temp_path("recordings");
+    let recordings = file.display().to_string();
+
+    let command = RecordReplayCommand::new(ShellCommand::new("fail", "false"), &recordings);
+    assert!(!command.execute().await.unwrap().success);
+    assert!(!file.exists());
+
+    let replay = RecordReplayCommand::new(ShellCommand::new("fail", "false"), &recordings)
+        .with_mode(RecordReplayMode::Replay);
+    assert!(replay.execute().await.is_err());
+
+    let recorded = RecordReplayCommand::new(ShellCommand::new("fail", "false"), &recordings)
+        .with_record_failures(true);
+    assert!(!recorded.execute().await.unwrap().success);
+    assert!(!replay.execute().await.unwrap().success);
+
+    let _ = std::fs::remove_file(&file);
+}
+
+#[tokio::test]
+async fn replay_returns_recorded_result_without_running_command() {
+    let file = temp_path("recordings");
+    let marker = temp_path("marker");
+    let recordings = file.display().to_string();
+    let command = format!("echo recorded; echo run >> {}", marker.display());
+
+    let recorder = RecordReplayCommand::new(ShellCommand::new("record", &command), &recordings)
+        .with_mode(RecordReplayMode::Record);
+    let recorded = recorder.execute().await.unwrap();
+    assert!(recorded.success);
+
+    let replayer = RecordReplayCommand::new(ShellCommand::new("record", &command), &recordings)
+        .with_mode(RecordReplayMode::Replay);
+    let replayed = replayer.execute().await.unwrap();
+
+    assert_eq!(replayed.output, recorded.output);
+    assert!(replayed.success);
+    assert_eq!(read_lines(&marker), vec!["run"]);
+
+    let _ = std::fs::remove_file(&file);
+    let _ = std::fs::remove_file(&marker);
+}
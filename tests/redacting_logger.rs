@@ -0,0 +1,50 @@
+mod common;
+
+use regex::Regex;
+use serde_json::json;
+
+use command_system::logging::LogContext;
+use command_system::{Logger, RedactingLogger};
+
+use common::MemoryLogger;
+
+fn redacting(inner: &MemoryLogger) -> RedactingLogger {
+    RedactingLogger::new(inner.boxed(), vec![Regex::new(r"password=(\S+)").unwrap()])
+}
+
+#[test]
+fn password_is_redacted_in_message() {
+    let inner = MemoryLogger::new();
+    let logger = redacting(&inner);
+
+    logger.info("connect --password=hunter2");
+
+    assert_eq!(inner.messages(), vec!["connect --password=***".to_string()]);
+}
+
+#[test]
+fn password_is_redacted_in_context() {
+    let inner = MemoryLogger::new();
+    let logger = redacting(&inner);
+
+    let context = LogContext::new()
+        .with_caller("login password=hunter2")
+        .with_extra(json!({
+            "command": "connect --password=hunter2",
+            "nested": { "args": ["password=hunter2", 42] },
+        }));
+    logger.log_with_context(command_system::LogLevel::Info, "password=hunter2", &context);
+
+    let entry = &inner.entries()[0];
+    let context = entry.context.as_ref().unwrap();
+    assert_eq!(entry.message, "password=***");
+    assert_eq!(context.caller.as_deref(), Some("login password=***"));
+    assert_eq!(
+        context.extra,
+        Some(json!({
+            "command": "connect --password=***",
+            "nested": { "args": ["password=***", 42] },
+        }))
+    );
+    assert!(!format!("{:?}", inner.entries()).contains("hunter2"));
+}
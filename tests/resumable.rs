@@ -0,0 +1,87 @@
+mod common;
+
+use command_system::command::ShellCommand;
+use command_system::CommandChain;
+
+use common::{read_lines, temp_path};
+
+/// Команда, отмечающая свое выполнение строкой в журнале
+fn marked(name: &str, log: &std::path::Path, extra: &str) -> ShellCommand {
+    ShellCommand::new(
+        name,
+        &format!("echo {} >> {}{}", name, log.display(), extra),
+    )
+}
+
+#[tokio::test]
+async fn resumed_chain_skips_completed_commands() {
+    let log = temp_path("resume_log");
+    let state = temp_path("resume_state");
+    let state_path = state.display().to_string();
+
+    // Первый запуск прерывается после первой команды
+    let mut partial = CommandChain::new("chain");
+    partial
+        .with_rollback_on_error(false)
+        .add_command(marked("first", &log, "; echo abc").with_capture_as("id"))
+        .add_command(ShellCommand::new("crash", "false"));
+    assert!(
+        !partial
+            .execute_resumable(&state_path)
+            .await
+            .unwrap()
+            .success
+    );
+    assert!(state.exists());
+
+    let mut full = CommandChain::new("chain");
+    full.add_command(marked("first", &log, "; echo abc").with_capture_as("id"))
+        .add_command(ShellCommand::new(
+            "crash",
+            &format!("echo 'id={{id}}' >> {}", log.display()),
+        ));
+    let result = full.execute_resumable(&state_path).await.unwrap();
+
+    assert!(result.success);
+    assert!(result.results[0].is_skipped());
+    assert_eq!(
+        read_lines(&log),
+        vec!["first".to_string(), "id=abc".to_string()]
+    );
+    assert!(!state.exists());
+    let _ = std::fs::remove_file(&log);
+}
+
+#[tokio::test]
+async fn commands_with_same_name_are_tracked_by_position() {
+    let log = temp_path("resume_dup_log");
+    let state = temp_path("resume_dup_state");
+    let state_path = state.display().to_string();
+
+    let mut partial = CommandChain::new("chain");
+    partial
+        .with_rollback_on_error(false)
+        .add_command(marked("step", &log, ""))
+        .add_command(ShellCommand::new("step", "false"));
+    assert!(
+        !partial
+            .execute_resumable(&state_path)
+            .await
+            .unwrap()
+            .success
+    );
+
+    let mut full = CommandChain::new("chain");
+    full.add_command(marked("step", &log, ""))
+        .add_command(marked("step", &log, ""));
+    let result = full.execute_resumable(&state_path).await.unwrap();
+
+    assert!(result.success);
+    assert!(result.results[0].is_skipped());
+    assert!(!result.results[1].is_skipped());
+    assert_eq!(
+        read_lines(&log),
+        vec!["step".to_string(), "step".to_string()]
+    );
+    let _ = std::fs::remove_file(&log);
+}
@@ -0,0 +1,78 @@
+use std::time::Duration;
+
+use command_system::command::RetryPolicy;
+
+#[test]
+fn delay_grows_exponentially_up_to_max() {
+    let policy = RetryPolicy::new(Duration::from_millis(100), Duration::from_millis(500));
+
+    assert_eq!(policy.delay(1), Duration::from_millis(100));
+    assert_eq!(policy.delay(2), Duration::from_millis(200));
+    assert_eq!(policy.delay(3), Duration::from_millis(400));
+    assert_eq!(policy.delay(4), Duration::from_millis(500));
+    assert_eq!(policy.delay(100), Duration::from_millis(500));
+}
+
+#[test]
+fn jitter_stays_within_bounds() {
+    let policy =
+        RetryPolicy::new(Duration::from_millis(100), Duration::from_secs(10)).with_jitter(true);
+
+    assert_eq!(policy.delay_with(1, || 0.0), Duration::from_millis(50));
+    assert_eq!(policy.delay_with(1, || 0.5), Duration::from_millis(100));
+
+    for _ in 0..100 {
+        let delay = policy.delay(2);
+        assert!(delay >= Duration::from_millis(100) && delay <= Duration::from_millis(300));
+    }
+}
+
+#[test]
+fn jitter_near_duration_max_does_not_overflow() {
+    let policy = RetryPolicy::new(Duration::MAX, Duration::MAX).with_jitter(true);
+
+    assert_eq!(policy.delay_with(1, || 0.99), Duration::MAX);
+    assert_eq!(policy.delay_with(64, || 0.99), Duration::MAX);
+}
+
+/// Детерминированный источник случайных чисел из `[0, 1)` (линейный конгруэнтный генератор)
+fn seeded(mut state: u64) -> impl FnMut() -> f64 {
+    move || {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (state >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+#[test]
+fn jitter_varies_delays_and_no_jitter_is_deterministic() {
+    let base = RetryPolicy::new(Duration::from_millis(100), Duration::from_secs(10));
+    let jittered = base.clone().with_jitter(true);
+
+    let mut random = seeded(42);
+    let delays: Vec<_> = (0..5)
+        .map(|_| jittered.delay_with(1, &mut random))
+        .collect();
+    assert!(delays.windows(2).any(|pair| pair[0] != pair[1]));
+
+    let mut replay = seeded(42);
+    let replayed: Vec<_> = (0..5)
+        .map(|_| jittered.delay_with(1, &mut replay))
+        .collect();
+    assert_eq!(delays, replayed);
+
+    let mut calls = 0;
+    let plain: Vec<_> = (0..5)
+        .map(|_| {
+            base.delay_with(1, || {
+                calls += 1;
+                0.9
+            })
+        })
+        .collect();
+    assert!(plain
+        .iter()
+        .all(|delay| *delay == Duration::from_millis(100)));
+    assert_eq!(calls, 0);
+}
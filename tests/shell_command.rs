@@ -0,0 +1,554 @@
+mod common;
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use command_system::command::traits::CommandError;
+use command_system::command::{CommandExecution, ExecutionContext, ShellCommand};
+use command_system::logging::OutputStream;
+use command_system::{CommandBuilder, ParsedResult};
+use serde_json::json;
+
+use common::{read_lines, temp_path, MemoryLogger};
+
+#[tokio::test]
+async fn echo_passes_output_lines_to_logger() {
+    let logger = MemoryLogger::new();
+    let command = ShellCommand::new("streams", "echo out; echo err 1>&2").with_echo(true);
+
+    let context = ExecutionContext::new().with_logger(&logger);
+    let result = command.execute_with_context(&context).await.unwrap();
+    assert!(result.success);
+
+    let lines: Vec<_> = logger
+        .entries()
+        .into_iter()
+        .filter_map(|e| e.stream.map(|stream| (stream, e.message)))
+        .collect();
+    assert!(lines.contains(&(OutputStream::Stdout, "out".to_string())));
+    assert!(lines.contains(&(OutputStream::Stderr, "err".to_string())));
+}
+
+#[tokio::test]
+async fn output_lines_are_not_logged_without_echo() {
+    let logger = MemoryLogger::new();
+    let command = ShellCommand::new("quiet", "echo out");
+
+    let context = ExecutionContext::new().with_logger(&logger);
+    command.execute_with_context(&context).await.unwrap();
+
+    assert!(logger.entries().iter().all(|e| e.stream.is_none()));
+}
+
+#[tokio::test]
+async fn streaming_reports_stream_of_each_line() {
+    let command = ShellCommand::new("streams", "echo out; echo err 1>&2");
+
+    let mut lines = Vec::new();
+    let result = command
+        .execute_streaming(|stream, line| lines.push((stream, line.to_string())))
+        .await
+        .unwrap();
+
+    assert!(result.success);
+    assert!(lines.contains(&(OutputStream::Stdout, "out".to_string())));
+    assert!(lines.contains(&(OutputStream::Stderr, "err".to_string())));
+}
+
+#[tokio::test]
+async fn heartbeat_is_logged_while_command_runs() {
+    let logger = MemoryLogger::new();
+    let command = ShellCommand::new("slow", "sleep 2").with_heartbeat(Duration::from_secs(1));
+
+    let context = ExecutionContext::new().with_logger(&logger);
+    let result = command.execute_with_context(&context).await.unwrap();
+
+    assert!(result.success);
+    assert!(logger.contains("Команда 'slow' выполняется (прошло 1 с)"));
+}
+
+#[tokio::test]
+async fn echo_logs_resolved_command_before_running() {
+    let logger = MemoryLogger::new();
+    let command = ShellCommand::new("greet", "echo hello").with_echo(true);
+
+    let context = ExecutionContext::new().with_logger(&logger);
+    command.execute_with_context(&context).await.unwrap();
+
+    let messages = logger.messages();
+    assert_eq!(messages.first().map(String::as_str), Some("+ echo hello"));
+}
+
+#[tokio::test]
+async fn output_parser_sets_success_and_data() {
+    let command = ShellCommand::new("status", "echo ready; exit 3").with_output_parser(
+        |output, exit_code| {
+            if output.contains("ready") {
+                ParsedResult::success(Some(json!({ "exit_code": exit_code })))
+            } else {
+                ParsedResult::failure("не готово")
+            }
+        },
+    );
+
+    let result = command.execute().await.unwrap();
+
+    assert!(result.success);
+    assert_eq!(result.data, Some(json!({ "exit_code": 3 })));
+}
+
+#[tokio::test]
+async fn output_parser_failure_sets_error() {
+    let command = ShellCommand::new("status", "echo starting")
+        .with_output_parser(|_, _| ParsedResult::failure("не готово"));
+
+    let result = command.execute().await.unwrap();
+
+    assert!(!result.success);
+    assert_eq!(result.error.as_deref(), Some("не готово"));
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn umask_applies_to_command() {
+    let command = ShellCommand::new("mask", "umask").with_umask(0o077);
+
+    let result = command.execute().await.unwrap();
+
+    assert!(result.success);
+    assert_eq!(result.output.trim(), "0077");
+}
+
+#[tokio::test]
+async fn success_codes_accept_listed_exit_code() {
+    let command = ShellCommand::new("exit", "sh -c 'exit 2'").with_success_codes(vec![2]);
+
+    let result = command.execute().await.unwrap();
+
+    assert!(result.success);
+    assert_eq!(result.exit_code, Some(2));
+}
+
+#[tokio::test]
+async fn streaming_delivers_lines_as_they_are_printed() {
+    let command = ShellCommand::new(
+        "count",
+        "sh -c 'for i in 1 2 3; do echo $i; sleep 0.1; done'",
+    );
+    let mut lines = Vec::new();
+
+    let result = command
+        .execute_streaming(|stream, line| lines.push((stream, line.to_string(), Instant::now())))
+        .await
+        .unwrap();
+
+    assert!(result.success);
+    let texts: Vec<_> = lines.iter().map(|(_, line, _)| line.as_str()).collect();
+    assert_eq!(texts, vec!["1", "2", "3"]);
+    assert!(lines
+        .iter()
+        .all(|(stream, _, _)| *stream == OutputStream::Stdout));
+    assert!(lines[2].2.duration_since(lines[0].2) >= Duration::from_millis(150));
+}
+
+#[tokio::test]
+async fn stdin_data_is_piped_into_command() {
+    let command = ShellCommand::new("sort", "sort").with_stdin("b\na\n");
+
+    let result = command.execute().await.unwrap();
+
+    assert!(result.success);
+    assert_eq!(result.output, "a\nb\n");
+}
+
+#[tokio::test]
+async fn clean_env_hides_parent_variables() {
+    std::env::set_var("COMMAND_SYSTEM_PARENT_VAR", "inherited");
+
+    let inherited = ShellCommand::new("env", "env").execute().await.unwrap();
+    let clean = ShellCommand::new("env", "env")
+        .with_clean_env(true)
+        .with_env_var("CHILD_VAR", "own")
+        .execute()
+        .await
+        .unwrap();
+
+    assert!(inherited
+        .output
+        .contains("COMMAND_SYSTEM_PARENT_VAR=inherited"));
+    assert!(clean.success);
+    assert!(!clean.output.contains("COMMAND_SYSTEM_PARENT_VAR"));
+    assert!(clean.output.contains("CHILD_VAR=own"));
+}
+
+#[tokio::test]
+async fn output_beyond_cap_is_truncated() {
+    let command =
+        ShellCommand::new("flood", "sh -c 'yes | head -c 100000'").with_max_output_bytes(1024);
+
+    let result = command.execute().await.unwrap();
+
+    assert!(result.success);
+    assert!(result.truncated);
+    assert!(result.output.len() <= 1024);
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn cpu_time_is_reported_for_busy_command() {
+    let command = ShellCommand::new(
+        "busy",
+        "sh -c 'i=0; while [ $i -lt 200000 ]; do i=$((i + 1)); done'",
+    );
+
+    let result = command.execute().await.unwrap();
+
+    assert!(result.success);
+    assert!(result.cpu_time_ms.unwrap() > 0);
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn custom_shell_runs_bash_only_construct() {
+    let command =
+        ShellCommand::new("bash", "[[ abc == a* ]] && echo matched").with_shell("bash", "-c");
+
+    let result = command.execute().await.unwrap();
+
+    assert!(result.success);
+    assert_eq!(result.output.trim(), "matched");
+}
+
+#[tokio::test]
+async fn env_file_variables_reach_command() {
+    let env_file = temp_path("env");
+    std::fs::write(&env_file, "FOO=bar\n").unwrap();
+
+    let command = ShellCommand::new("foo", "sh -c 'echo $FOO'")
+        .with_env_file(&env_file.display().to_string());
+
+    let result = command.execute().await.unwrap();
+    let _ = std::fs::remove_file(&env_file);
+
+    assert!(result.success);
+    assert_eq!(result.output.trim(), "bar");
+}
+
+#[tokio::test]
+async fn resolved_command_reflects_substituted_variables() {
+    let variables = HashMap::from([("target".to_string(), "world".to_string())]);
+    let command = ShellCommand::new("greet", "echo hello {target}");
+
+    let result = command
+        .execute_with_context(&ExecutionContext::new().with_variables(&variables))
+        .await
+        .unwrap();
+
+    assert!(result.success);
+    assert_eq!(result.resolved_command, "echo hello world");
+}
+
+#[tokio::test]
+async fn tags_flow_from_builder_to_result() {
+    let command = CommandBuilder::new("deploy", "true")
+        .tag("team", "platform")
+        .tag("env", "prod")
+        .build();
+
+    assert_eq!(
+        command.tags().get("team").map(String::as_str),
+        Some("platform")
+    );
+
+    let result = command.execute().await.unwrap();
+
+    assert_eq!(result.tags, command.tags().clone());
+    assert_eq!(result.tags.get("env").map(String::as_str), Some("prod"));
+}
+
+#[tokio::test]
+async fn timeout_keeps_partial_output() {
+    let command = ShellCommand::new("slow", "sh -c 'echo started; sleep 5'")
+        .with_timeout_duration(Duration::from_millis(500));
+
+    let result = command.execute().await.unwrap();
+
+    assert!(result.is_timeout());
+    assert!(!result.success);
+    assert_eq!(result.output.trim(), "started");
+}
+
+#[tokio::test]
+async fn fixed_id_is_reused_across_runs() {
+    let command = ShellCommand::new("deploy", "true").with_id("deploy-2024");
+
+    let first = command.execute().await.unwrap();
+    let second = command.execute().await.unwrap();
+    let random = ShellCommand::new("deploy", "true").execute().await.unwrap();
+
+    assert_eq!(first.id, "deploy-2024");
+    assert_eq!(first.id, second.id);
+    assert_ne!(random.id, first.id);
+}
+
+#[tokio::test]
+async fn missing_working_dir_is_reported() {
+    let dir = temp_path("missing_dir");
+    let dir_path = dir.display().to_string();
+
+    let result = ShellCommand::new("list", "ls")
+        .with_working_dir(&dir_path)
+        .execute()
+        .await;
+
+    let error = result.unwrap_err().to_string();
+    assert!(error.contains("не существует"));
+    assert!(error.contains(&dir_path));
+}
+
+#[tokio::test]
+async fn missing_working_dir_is_created_on_request() {
+    let dir = temp_path("created_dir");
+
+    let result = ShellCommand::new("pwd", "pwd")
+        .with_working_dir(&dir.display().to_string())
+        .with_create_working_dir(true)
+        .execute()
+        .await
+        .unwrap();
+
+    assert!(result.success);
+    assert!(dir.is_dir());
+    assert_eq!(
+        std::fs::canonicalize(result.output.trim()).unwrap(),
+        std::fs::canonicalize(&dir).unwrap()
+    );
+    let _ = std::fs::remove_dir(&dir);
+}
+
+#[cfg(windows)]
+#[tokio::test]
+async fn powershell_runs_cmdlet() {
+    let command = ShellCommand::new("date", "Get-Date -Format yyyy").with_powershell();
+
+    let result = command.execute().await.unwrap();
+
+    assert!(result.success);
+    assert_eq!(result.output.trim().len(), 4);
+    assert!(result.output.trim().chars().all(|c| c.is_ascii_digit()));
+}
+
+#[cfg(windows)]
+#[tokio::test]
+async fn powershell_expands_percent_variables() {
+    let user = std::env::var("USERNAME").unwrap();
+    let command = ShellCommand::new("whoami", "Write-Output %USERNAME%")
+        .with_powershell()
+        .with_windows_env_expansion(true);
+
+    let result = command.execute().await.unwrap();
+
+    assert!(result.success);
+    assert_eq!(result.output.trim(), user);
+}
+
+#[tokio::test]
+async fn percent_variables_expand_from_command_env() {
+    let command = ShellCommand::new("greet", "echo %GREETING% %UNKNOWN_VARIABLE%")
+        .with_env_var("GREETING", "hello")
+        .with_windows_env_expansion(true);
+
+    let result = command.execute().await.unwrap();
+
+    assert!(result.success);
+    assert_eq!(result.output.trim(), "hello %UNKNOWN_VARIABLE%");
+}
+
+#[tokio::test]
+async fn percent_variable_lookup_prefers_exact_name() {
+    // Каждая команда получает свой порядок обхода переменных
+    for _ in 0..20 {
+        let command = ShellCommand::new("stage", "echo %STAGE% %stage%")
+            .with_env_var("Stage", "mixed")
+            .with_env_var("STAGE", "upper")
+            .with_windows_env_expansion(true);
+
+        let result = command.execute().await.unwrap();
+
+        // Без учета регистра из "STAGE" и "Stage" выбирается наименьшее имя
+        let expected = if cfg!(windows) {
+            "upper upper"
+        } else {
+            "upper %stage%"
+        };
+        assert_eq!(result.output.trim(), expected);
+    }
+}
+
+#[tokio::test]
+async fn binary_output_keeps_invalid_utf8_bytes() {
+    let command = ShellCommand::new("bytes", r"printf '\377\376ok\200'").with_binary_output(true);
+
+    let result = command.execute().await.unwrap();
+
+    assert!(result.success);
+    assert_eq!(result.stdout_bytes, vec![0xff, 0xfe, b'o', b'k', 0x80]);
+}
+
+#[test]
+fn execute_blocking_works_without_async_runtime() {
+    let result = ShellCommand::new("echo", "echo blocking")
+        .execute_blocking()
+        .unwrap();
+
+    assert!(result.success);
+    assert_eq!(result.output.trim(), "blocking");
+}
+
+#[tokio::test]
+async fn wait_until_polls_until_output_matches() {
+    let counter = temp_path("poll_counter");
+    let script = format!("sh -c 'echo x >> {0}; wc -l < {0}'", counter.display());
+    let ready = regex::Regex::new(r"^\s*3\s*$").unwrap();
+
+    let polled = ShellCommand::new("poll", &script)
+        .with_wait_until(ready.clone(), Duration::from_millis(50), 10)
+        .execute()
+        .await
+        .unwrap();
+
+    assert!(polled.success);
+    assert_eq!(polled.output.trim(), "3");
+    assert_eq!(read_lines(&counter).len(), 3);
+    let _ = std::fs::remove_file(&counter);
+
+    let exhausted = ShellCommand::new("poll", &script)
+        .with_wait_until(ready, Duration::from_millis(50), 2)
+        .execute()
+        .await
+        .unwrap();
+
+    assert!(exhausted.is_timeout());
+    assert_eq!(read_lines(&counter).len(), 2);
+    let _ = std::fs::remove_file(&counter);
+}
+
+#[tokio::test]
+async fn missing_program_is_spawn_error() {
+    // Командная строка выполняется интерпретатором, поэтому запускаемой
+    // программой является сам интерпретатор
+    let result = ShellCommand::new("missing", "true")
+        .with_shell("command-system-no-such-program", "-c")
+        .execute()
+        .await;
+
+    match result {
+        Err(CommandError::SpawnError(message)) => {
+            assert!(message.contains("command-system-no-such-program"))
+        }
+        other => panic!("ожидалась ошибка запуска, получено {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn expected_output_passes_when_present() {
+    let result = ShellCommand::new("status", "echo service is healthy")
+        .with_expect_output_contains("healthy")
+        .execute()
+        .await
+        .unwrap();
+
+    assert!(result.success);
+}
+
+#[tokio::test]
+async fn expected_output_fails_when_missing() {
+    let result = ShellCommand::new("status", "echo service is degraded")
+        .with_expect_output_contains("healthy")
+        .execute()
+        .await
+        .unwrap();
+
+    assert!(!result.success);
+    assert!(result.error.unwrap().contains("healthy"));
+}
+
+#[tokio::test]
+async fn execute_with_logger_reports_start_and_finish() {
+    let logger = MemoryLogger::new();
+
+    let result = ShellCommand::new("build", "true")
+        .execute_with_logger(&logger)
+        .await
+        .unwrap();
+
+    assert!(result.success);
+    let messages = logger.messages();
+    assert_eq!(messages.first().unwrap(), "Выполнение команды 'build'");
+    assert_eq!(
+        messages.last().unwrap(),
+        "Команда 'build' успешно выполнена"
+    );
+    assert!(logger.entries().iter().all(|entry| entry
+        .context
+        .as_ref()
+        .unwrap()
+        .extra
+        .as_ref()
+        .unwrap()["command"]
+        == "build"));
+}
+
+#[tokio::test]
+async fn spawn_latency_is_part_of_total_duration() {
+    let vars = temp_path("latency_vars");
+    std::fs::write(&vars, r#"{"delay": "0.1"}"#).unwrap();
+
+    let result = ShellCommand::new("sleep", "sleep {#delay}")
+        .with_variables_file(&vars.display().to_string())
+        .execute()
+        .await
+        .unwrap();
+    let _ = std::fs::remove_file(&vars);
+
+    assert!(result.success);
+    assert!(result.exec_ms >= 100);
+    assert!(result.exec_ms <= result.duration_ms);
+    assert!(result.spawn_latency_ms <= result.duration_ms);
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn timeout_sends_sigterm_before_kill() {
+    let log = temp_path("sigterm");
+    let command = ShellCommand::new(
+        "trap",
+        &format!(
+            "trap 'echo got-term >> {}; exit 0' TERM; sleep 5 & wait",
+            log.display()
+        ),
+    )
+    .with_timeout_duration(Duration::from_millis(300))
+    .with_kill_grace(Duration::from_secs(2));
+
+    let started = Instant::now();
+    let result = command.execute().await.unwrap();
+
+    assert!(result.is_timeout());
+    assert!(started.elapsed() < Duration::from_secs(2));
+    assert_eq!(read_lines(&log), vec!["got-term".to_string()]);
+    let _ = std::fs::remove_file(&log);
+}
+
+#[tokio::test]
+async fn sub_second_timeout_is_enforced() {
+    let started = Instant::now();
+    let result = ShellCommand::new("sleep", "sleep 1")
+        .with_timeout_duration(Duration::from_millis(500))
+        .execute()
+        .await
+        .unwrap();
+
+    assert!(result.is_timeout());
+    assert!(started.elapsed() < Duration::from_millis(900));
+}
@@ -0,0 +1,26 @@
+mod common;
+
+use command_system::logging::TracingLogger;
+use command_system::{LogLevel, Logger};
+
+use common::SharedBuffer;
+
+#[test]
+fn info_message_reaches_subscriber() {
+    let buffer = SharedBuffer::default();
+    let writer = buffer.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_writer(move || writer.clone())
+        .with_ansi(false)
+        .finish();
+
+    tracing::subscriber::with_default(subscriber, || {
+        TracingLogger::new(LogLevel::Info).info("chain started");
+        TracingLogger::new(LogLevel::Info).debug("filtered out");
+    });
+
+    let contents = buffer.contents();
+    assert!(contents.contains("INFO"));
+    assert!(contents.contains("chain started"));
+    assert!(!contents.contains("filtered out"));
+}
@@ -0,0 +1,134 @@
+use async_trait::async_trait;
+use command_system::command::traits::CommandError;
+use command_system::command::{
+    Command, CommandExecution, CommandResult, CompositeCommand, ConditionalCommand, ExecutionMode,
+    ShellCommand,
+};
+use command_system::visitor::ValidationRule;
+use command_system::{MetricsVisitor, ValidationVisitor, Visitor};
+
+#[test]
+fn metrics_count_nested_commands() {
+    let mut inner = CompositeCommand::new("inner");
+    inner
+        .with_execution_mode(ExecutionMode::Parallel)
+        .add_command(ShellCommand::new("lint", "true"));
+
+    let mut outer = CompositeCommand::new("outer");
+    outer
+        .add_command(ShellCommand::new("build", "true").with_rollback("true"))
+        .add_command(ShellCommand::new("test", "true"))
+        .add_command(inner);
+
+    let mut metrics = MetricsVisitor::new();
+    outer.accept(&mut metrics);
+
+    assert_eq!(metrics.shell_commands(), 3);
+    assert_eq!(metrics.composite_commands(), 2);
+    assert_eq!(metrics.generic_commands(), 0);
+    assert_eq!(metrics.total_commands(), 5);
+    assert_eq!(metrics.rollback_commands(), 1);
+    assert!(metrics.execution_modes().contains(&ExecutionMode::Parallel));
+}
+
+#[test]
+fn destructive_command_produces_warning() {
+    let mut unsafe_visitor = ValidationVisitor::new();
+    ShellCommand::new("wipe", "rm -rf /").accept(&mut unsafe_visitor);
+
+    let mut safe_visitor = ValidationVisitor::new();
+    ShellCommand::new("list", "ls -la").accept(&mut safe_visitor);
+
+    assert_eq!(unsafe_visitor.warnings().len(), 1);
+    let warning = &unsafe_visitor.warnings()[0];
+    assert_eq!(warning.command_name, "wipe");
+    assert_eq!(warning.rule, ValidationRule::DestructiveRemove);
+    assert!(!unsafe_visitor.is_valid());
+
+    assert!(safe_visitor.warnings().is_empty());
+    assert!(safe_visitor.is_valid());
+}
+
+/// Визитор, записывающий посещенные узлы
+#[derive(Default)]
+struct RecordingVisitor {
+    visited: Vec<String>,
+}
+
+impl Visitor for RecordingVisitor {
+    fn visit_shell_command(&mut self, command: &ShellCommand) {
+        self.visited.push(format!("shell {}", command.name()));
+    }
+
+    fn visit_composite_command(&mut self, command: &CompositeCommand) {
+        self.visited.push(format!("composite {}", command.name()));
+    }
+
+    fn visit_conditional_command(&mut self, command: &ConditionalCommand) {
+        self.visited.push(format!("conditional {}", command.name()));
+    }
+
+    fn visit_generic_command(&mut self, name: &str, kind: &str) {
+        self.visited.push(format!("{} {}", kind, name));
+    }
+}
+
+#[test]
+fn custom_visitor_sees_conditional_command() {
+    let command = ConditionalCommand::new(ShellCommand::new("deploy", "true"), |_| true);
+
+    let mut visitor = RecordingVisitor::default();
+    command.accept(&mut visitor);
+
+    assert_eq!(visitor.visited, vec!["conditional deploy", "shell deploy"]);
+}
+
+/// Пользовательская команда, выполняющая HTTP-запрос (без сети в тестах)
+struct HttpCommand {
+    name: String,
+    url: String,
+}
+
+#[async_trait]
+impl CommandExecution for HttpCommand {
+    async fn execute(&self) -> Result<CommandResult, CommandError> {
+        Ok(CommandResult::new(&self.name).success(format!("GET {}", self.url)))
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn execution_mode(&self) -> ExecutionMode {
+        ExecutionMode::Sequential
+    }
+}
+
+impl Command for HttpCommand {}
+
+#[tokio::test]
+async fn visitor_sees_custom_command_type() {
+    let http = HttpCommand {
+        name: "health".to_string(),
+        url: "http://localhost/health".to_string(),
+    };
+    let result = http.execute().await.unwrap();
+    assert_eq!(result.output, "GET http://localhost/health");
+
+    let mut composite = CompositeCommand::new("deploy");
+    composite
+        .add_command(ShellCommand::new("build", "true"))
+        .add_command(http);
+
+    let mut visitor = RecordingVisitor::default();
+    composite.accept(&mut visitor);
+    assert_eq!(
+        visitor.visited,
+        vec!["composite deploy", "shell build", "HttpCommand health"]
+    );
+
+    let mut metrics = MetricsVisitor::new();
+    composite.accept(&mut metrics);
+    assert_eq!(metrics.generic_commands(), 1);
+    assert_eq!(metrics.shell_commands(), 1);
+}